@@ -3,4 +3,23 @@ use std::env;
 
 lazy_static! {
     pub static ref DATA_PATH: String = env::var("DATA_PATH").unwrap();
+    // used to build t.me deep links to pages
+    pub static ref BOT_NAME: String = env::var("BOT_NAME").unwrap_or("ksbd_bot".to_string());
+    // when set, texts are translated right in the chat instead of a link to google translate
+    pub static ref LIBRETRANSLATE_URL: Option<String> = env::var("LIBRETRANSLATE_URL").ok();
+    pub static ref LIBRETRANSLATE_API_KEY: Option<String> = env::var("LIBRETRANSLATE_API_KEY").ok();
+    // more new pages than that in one poll are announced with a single message
+    pub static ref BURST_THRESHOLD: usize = env::var("BURST_THRESHOLD")
+        .ok()
+        .and_then(|t| t.parse().ok())
+        .unwrap_or(3);
+    // channels every new page is published to, comma-separated ids or @usernames
+    pub static ref CHANNELS: Vec<String> = env::var("CHANNELS")
+        .map(|cs| {
+            cs.split(',')
+                .map(|c| c.trim().to_string())
+                .filter(|c| !c.is_empty())
+                .collect()
+        })
+        .unwrap_or_default();
 }
@@ -0,0 +1,87 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use teloxide::prelude::ChatId;
+
+#[derive(Debug, Default, Clone)]
+pub struct BookmarksState {
+    bookmarks: HashMap<i64, BTreeSet<usize>>,
+}
+
+impl BookmarksState {
+    // false if the page was bookmarked already
+    pub fn add(&mut self, chat_id: ChatId, idx: usize) -> bool {
+        self.bookmarks.entry(chat_id.0).or_default().insert(idx)
+    }
+
+    pub fn remove(&mut self, chat_id: ChatId, idx: usize) {
+        if let Some(idxs) = self.bookmarks.get_mut(&chat_id.0) {
+            idxs.remove(&idx);
+            if idxs.is_empty() {
+                self.bookmarks.remove(&chat_id.0);
+            }
+        }
+    }
+
+    pub fn by_chat(&self, chat_id: ChatId) -> Vec<usize> {
+        self.bookmarks
+            .get(&chat_id.0)
+            .map(|idxs| idxs.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    pub fn migrate(&mut self, from: ChatId, to: ChatId) -> bool {
+        match self.bookmarks.remove(&from.0) {
+            Some(bookmarks) => {
+                self.bookmarks.insert(to.0, bookmarks);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl FromStr for BookmarksState {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bookmarks = s
+            .split('\n')
+            .filter(|l| !l.is_empty())
+            .map(|l| {
+                let l_split = l.split('\t').collect::<Vec<_>>();
+                (
+                    l_split[0].parse::<i64>().unwrap(),
+                    l_split[1]
+                        .split('|')
+                        .map(|idx| idx.parse::<usize>().unwrap())
+                        .collect::<BTreeSet<_>>(),
+                )
+            })
+            .collect::<HashMap<_, _>>();
+
+        Ok(BookmarksState { bookmarks })
+    }
+}
+
+impl Display for BookmarksState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let lines = self
+            .bookmarks
+            .iter()
+            .map(|(uid, idxs)| {
+                format!(
+                    "{}\t{}",
+                    uid,
+                    idxs.iter()
+                        .map(|idx| idx.to_string())
+                        .collect::<Vec<_>>()
+                        .join("|")
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        write!(f, "{}", lines)
+    }
+}
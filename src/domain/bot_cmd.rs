@@ -1,5 +1,8 @@
+use teloxide::types::BotCommand;
 use teloxide::utils::command::BotCommands;
 
+use crate::domain::i18n::{Lang, Msg};
+
 #[derive(BotCommands, Clone)]
 #[command(
     rename_rule = "lowercase",
@@ -7,7 +10,7 @@ use teloxide::utils::command::BotCommands;
 )]
 pub enum Command {
     #[command(description = "starting the bot.")]
-    Start,
+    Start(String),
     #[command(description = "displays available commands.")]
     Help,
     #[command(description = "gets first page.")]
@@ -16,4 +19,64 @@ pub enum Command {
     Last,
     #[command(description = "shows jump-to menu.")]
     Jump,
+    #[command(description = "gets random page.")]
+    Random,
+    #[command(description = "gets pages published on this day in previous years.")]
+    OnThisDay,
+    #[command(description = "lists bookmarked pages.")]
+    Bookmarks,
+    #[command(description = "adds a note to the current page.")]
+    Note(String),
+    #[command(description = "lists your notes.")]
+    Notes,
+    #[command(description = "shows recently opened pages.")]
+    History,
+    #[command(description = "shows chat settings.")]
+    Settings,
+    #[command(description = "sets chat's timezone as utc offset, e.g. /timezone +3.")]
+    Timezone(String),
+    #[command(description = "rereads the archive a few pages a day, e.g. /reread 1 2.")]
+    Reread(String),
+    #[command(description = "pauses rereading.")]
+    Pause,
+    #[command(
+        description = "starts a book club in a group, e.g. /club 1 3, or stops it with /club off."
+    )]
+    Club(String),
+    #[command(description = "removes sent pages from the chat.")]
+    Clean,
+}
+
+// what's shown in telegram's command menu, in a given language
+pub fn bot_commands(lang: Lang) -> Vec<BotCommand> {
+    vec![
+        ("first", Msg::CmdFirst),
+        ("last", Msg::CmdLast),
+        ("jump", Msg::CmdJump),
+        ("random", Msg::CmdRandom),
+        ("onthisday", Msg::CmdOnThisDay),
+        ("bookmarks", Msg::CmdBookmarks),
+        ("note", Msg::CmdNote),
+        ("notes", Msg::CmdNotes),
+        ("history", Msg::CmdHistory),
+        ("settings", Msg::CmdSettings),
+        ("timezone", Msg::CmdTimezone),
+        ("reread", Msg::CmdReread),
+        ("pause", Msg::CmdPause),
+        ("club", Msg::CmdClub),
+        ("clean", Msg::CmdClean),
+        ("help", Msg::CmdHelp),
+    ]
+    .into_iter()
+    .map(|(cmd, description)| BotCommand::new(cmd, description.text(lang)))
+    .collect()
+}
+
+pub fn help_text(lang: Lang) -> String {
+    let cmds = bot_commands(lang)
+        .into_iter()
+        .map(|c| format!("/{} — {}", c.command, c.description))
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!("{}\n\n{}", Msg::HelpHeader.text(lang), cmds)
 }
@@ -1,7 +1,30 @@
+use crate::domain::bookmarks_state::BookmarksState;
+use crate::domain::channels_state::ChannelsState;
+use crate::domain::club_state::ClubState;
+use crate::domain::file_ids_state::FileIdsState;
+use crate::domain::history_state::HistoryState;
+use crate::domain::notes_state::NotesState;
 use crate::domain::pages_state::PagesState;
+use crate::domain::pins_state::PinsState;
+use crate::domain::queue_state::QueueState;
+use crate::domain::reread_state::RereadState;
+use crate::domain::sent_state::SentState;
+use crate::domain::settings_state::SettingsState;
 use crate::domain::subs_state::SubsState;
 
 pub struct BotState {
     pub pages: PagesState,
     pub subscribers: SubsState,
+    pub file_ids: FileIdsState,
+    pub bookmarks: BookmarksState,
+    pub notes: NotesState,
+    pub history: HistoryState,
+    pub settings: SettingsState,
+    pub queue: QueueState,
+    pub digest: QueueState,
+    pub reread: RereadState,
+    pub clubs: ClubState,
+    pub channels: ChannelsState,
+    pub pins: PinsState,
+    pub sent: SentState,
 }
@@ -0,0 +1,65 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+// the last page posted to every publishing channel, so restarts don't post anything twice.
+// and how many albums of the page after it are out already, if it failed halfway
+#[derive(Debug, Default, Clone)]
+pub struct ChannelsState {
+    posted: HashMap<String, usize>,
+    albums: HashMap<String, usize>,
+}
+
+impl ChannelsState {
+    pub fn last_posted(&self, channel: &str) -> Option<usize> {
+        self.posted.get(channel).cloned()
+    }
+
+    pub fn set_posted(&mut self, channel: &str, idx: usize) {
+        self.posted.insert(channel.to_string(), idx);
+        self.albums.remove(channel);
+    }
+
+    pub fn albums_posted(&self, channel: &str) -> usize {
+        self.albums.get(channel).cloned().unwrap_or_default()
+    }
+
+    pub fn set_albums_posted(&mut self, channel: &str, n: usize) {
+        self.albums.insert(channel.to_string(), n);
+    }
+}
+
+impl FromStr for ChannelsState {
+    type Err = String;
+
+    // albums column is missing in files written before it was added
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut state = ChannelsState::default();
+        for l in s.split('\n').filter(|l| !l.is_empty()) {
+            let l_split = l.split('\t').collect::<Vec<_>>();
+            let channel = l_split[0].to_string();
+            state
+                .posted
+                .insert(channel.clone(), l_split[1].parse::<usize>().unwrap());
+            if let Some(albums) = l_split.get(2) {
+                state
+                    .albums
+                    .insert(channel, albums.parse::<usize>().unwrap());
+            }
+        }
+
+        Ok(state)
+    }
+}
+
+impl Display for ChannelsState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let lines = self
+            .posted
+            .iter()
+            .map(|(channel, idx)| format!("{}\t{}\t{}", channel, idx, self.albums_posted(channel)))
+            .collect::<Vec<_>>()
+            .join("\n");
+        write!(f, "{}", lines)
+    }
+}
@@ -0,0 +1,147 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+use teloxide::prelude::ChatId;
+
+// a group reading the archive together, round by round
+#[derive(Debug, Clone, PartialEq)]
+pub struct Club {
+    // the first page of the next round, the shared position of the group
+    pub next: usize,
+    pub per_round: usize,
+    // ready votes needed to move on to the next round
+    pub quorum: usize,
+    // local hour rounds are posted at
+    pub hour: u32,
+    // local date of the last round
+    pub last_sent: Option<NaiveDate>,
+    // members ready for the next round
+    pub votes: BTreeSet<u64>,
+}
+
+impl Club {
+    pub fn new(from: usize, per_round: usize, quorum: usize, hour: u32) -> Club {
+        Club {
+            next: from,
+            per_round,
+            quorum,
+            hour,
+            last_sent: None,
+            votes: BTreeSet::new(),
+        }
+    }
+
+    // the first round goes without votes, the next ones wait for the quorum. at most a round a day
+    pub fn is_due(&self, local_hour: u32, local_date: NaiveDate) -> bool {
+        local_hour >= self.hour
+            && self.last_sent != Some(local_date)
+            && (self.last_sent.is_none() || self.votes.len() >= self.quorum)
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct ClubState {
+    clubs: HashMap<i64, Club>,
+}
+
+impl ClubState {
+    pub fn by_chat(&self, chat_id: ChatId) -> Option<Club> {
+        self.clubs.get(&chat_id.0).cloned()
+    }
+
+    pub fn set(&mut self, chat_id: ChatId, club: Club) {
+        self.clubs.insert(chat_id.0, club);
+    }
+
+    pub fn remove(&mut self, chat_id: ChatId) -> bool {
+        self.clubs.remove(&chat_id.0).is_some()
+    }
+
+    // votes for a round other than the upcoming one are stale and don't count
+    pub fn vote(&mut self, chat_id: ChatId, user_id: u64, next: usize) -> Option<Club> {
+        match self.clubs.get_mut(&chat_id.0) {
+            Some(club) if club.next == next => {
+                club.votes.insert(user_id);
+                Some(club.clone())
+            }
+            _ => None,
+        }
+    }
+
+    pub fn chat_ids(&self) -> Vec<ChatId> {
+        self.clubs.keys().map(|id| ChatId(*id)).collect()
+    }
+
+    pub fn migrate(&mut self, from: ChatId, to: ChatId) -> bool {
+        match self.clubs.remove(&from.0) {
+            Some(club) => {
+                self.clubs.insert(to.0, club);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl FromStr for ClubState {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let clubs = s
+            .split('\n')
+            .filter(|l| !l.is_empty())
+            .map(|l| {
+                let l_split = l.split('\t').collect::<Vec<_>>();
+                let club = Club {
+                    next: l_split[1].parse().unwrap(),
+                    per_round: l_split[2].parse().unwrap(),
+                    quorum: l_split[3].parse().unwrap(),
+                    hour: l_split[4].parse().unwrap(),
+                    last_sent: NaiveDate::parse_from_str(l_split[5], "%Y-%m-%d").ok(),
+                    votes: l_split[6]
+                        .split('|')
+                        .filter_map(|uid| uid.parse::<u64>().ok())
+                        .collect(),
+                };
+                (l_split[0].parse::<i64>().unwrap(), club)
+            })
+            .collect::<HashMap<_, _>>();
+
+        Ok(ClubState { clubs })
+    }
+}
+
+impl Display for ClubState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let lines = self
+            .clubs
+            .iter()
+            .map(|(uid, c)| {
+                format!(
+                    "{}\t{}\t{}\t{}\t{}\t{}\t{}",
+                    uid,
+                    c.next,
+                    c.per_round,
+                    c.quorum,
+                    c.hour,
+                    c.last_sent
+                        .map(|d| d.format("%Y-%m-%d").to_string())
+                        .unwrap_or("NO".to_string()),
+                    match c.votes.is_empty() {
+                        true => "NO".to_string(),
+                        false => c
+                            .votes
+                            .iter()
+                            .map(|v| v.to_string())
+                            .collect::<Vec<_>>()
+                            .join("|"),
+                    }
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        write!(f, "{}", lines)
+    }
+}
@@ -0,0 +1,54 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+// telegram file ids of already uploaded page images, in the same order as KsbdPage.imgs
+#[derive(Debug, Default, Clone)]
+pub struct FileIdsState {
+    file_ids: HashMap<usize, Vec<String>>,
+}
+
+impl FileIdsState {
+    pub fn add(&mut self, idx: usize, ids: Vec<String>) {
+        self.file_ids.insert(idx, ids);
+    }
+
+    pub fn by_idx(&self, idx: usize) -> Option<&Vec<String>> {
+        self.file_ids.get(&idx)
+    }
+}
+
+impl FromStr for FileIdsState {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let file_ids = s
+            .split('\n')
+            .filter(|l| !l.is_empty())
+            .map(|l| {
+                let l_split = l.split('\t').collect::<Vec<_>>();
+                (
+                    l_split[0].parse::<usize>().unwrap(),
+                    l_split[1]
+                        .split('|')
+                        .map(|id| id.to_string())
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect::<HashMap<_, _>>();
+
+        Ok(FileIdsState { file_ids })
+    }
+}
+
+impl Display for FileIdsState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let lines = self
+            .file_ids
+            .iter()
+            .map(|(idx, ids)| format!("{}\t{}", idx, ids.join("|")))
+            .collect::<Vec<_>>()
+            .join("\n");
+        write!(f, "{}", lines)
+    }
+}
@@ -0,0 +1,105 @@
+use std::collections::{HashMap, VecDeque};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use teloxide::prelude::ChatId;
+
+static MAX_HISTORY_LEN: usize = 20;
+
+// pages opened by chats, the latest is the last. reading page by page (PREV/NEXT) only moves
+// the latest entry, so the entry before it is always where the chat was before a jump
+#[derive(Debug, Default, Clone)]
+pub struct HistoryState {
+    history: HashMap<i64, VecDeque<usize>>,
+}
+
+impl HistoryState {
+    pub fn record(&mut self, chat_id: ChatId, idx: usize) {
+        let pages = self.history.entry(chat_id.0).or_default();
+        match pages.back().cloned() {
+            Some(last) if last == idx => {}
+            Some(last) if last + 1 == idx || idx + 1 == last => {
+                pages.pop_back();
+                pages.push_back(idx);
+            }
+            _ => {
+                pages.push_back(idx);
+                if pages.len() > MAX_HISTORY_LEN {
+                    pages.pop_front();
+                }
+            }
+        }
+    }
+
+    // forgets everything opened after the latest visit of the page
+    pub fn back_to(&mut self, chat_id: ChatId, idx: usize) {
+        if let Some(pages) = self.history.get_mut(&chat_id.0) {
+            if let Some(pos) = pages.iter().rposition(|p| *p == idx) {
+                pages.truncate(pos + 1);
+            }
+        }
+    }
+
+    pub fn by_chat(&self, chat_id: ChatId) -> Vec<usize> {
+        self.history
+            .get(&chat_id.0)
+            .map(|pages| pages.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    pub fn migrate(&mut self, from: ChatId, to: ChatId) -> bool {
+        match self.history.remove(&from.0) {
+            Some(history) => {
+                self.history.insert(to.0, history);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl FromStr for HistoryState {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let history = s
+            .split('\n')
+            .filter(|l| !l.is_empty())
+            .map(|l| {
+                let l_split = l.split('\t').collect::<Vec<_>>();
+                (
+                    l_split[0].parse::<i64>().unwrap(),
+                    l_split[1]
+                        .split('|')
+                        .map(|idx| idx.parse::<usize>().unwrap())
+                        .collect::<VecDeque<_>>(),
+                )
+            })
+            .collect::<HashMap<_, _>>();
+
+        Ok(HistoryState { history })
+    }
+}
+
+impl Display for HistoryState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let lines = self
+            .history
+            .iter()
+            .filter(|(_, pages)| !pages.is_empty())
+            .map(|(uid, pages)| {
+                format!(
+                    "{}\t{}",
+                    uid,
+                    pages
+                        .iter()
+                        .map(|idx| idx.to_string())
+                        .collect::<Vec<_>>()
+                        .join("|")
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        write!(f, "{}", lines)
+    }
+}
@@ -0,0 +1,328 @@
+// author's commentary is kept as telegram's html: a few formatting tags, escaped text in between
+
+pub fn escape(txt: &str) -> String {
+    txt.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// text as telegram shows it, e.g. for translators
+pub fn strip_tags(html: &str) -> String {
+    tokens(html)
+        .into_iter()
+        .filter_map(|t| match t {
+            Token::Text(t) => Some(t.to_string()),
+            Token::Entity(e) => Some(unescape(e)),
+            Token::Open(..) | Token::Close(_) => None,
+        })
+        .collect()
+}
+
+fn unescape(entity: &str) -> String {
+    match entity {
+        "&amp;" => "&".to_string(),
+        "&lt;" => "<".to_string(),
+        "&gt;" => ">".to_string(),
+        "&quot;" => "\"".to_string(),
+        other => other.to_string(),
+    }
+}
+
+// telegram counts message length in utf-16 code units of the text it shows, tags excluded
+pub const MAX_TEXT_LEN: usize = 4096;
+pub const MAX_CAPTION_LEN: usize = 1024;
+
+// length of plain text as telegram counts it
+pub fn utf16_len(txt: &str) -> usize {
+    txt.encode_utf16().count()
+}
+
+// length of html as telegram counts it, once tags are gone and entities are chars
+pub fn text_len(html: &str) -> usize {
+    tokens(html).iter().map(Token::len).sum()
+}
+
+// plain text cut to fit max_len, with an ellipsis if anything was cut
+pub fn truncate(txt: &str, max_len: usize) -> String {
+    if utf16_len(txt) <= max_len {
+        return txt.to_string();
+    }
+    let mut len = 1;
+    let mut truncated = txt
+        .chars()
+        .take_while(|c| {
+            len += c.len_utf16();
+            len <= max_len
+        })
+        .collect::<String>();
+    truncated.push('…');
+    truncated
+}
+
+// paragraphs are merged into as few messages as possible, too long ones are split
+pub fn split(paragraphs: &[String], max_len: usize) -> Vec<String> {
+    paragraphs
+        .iter()
+        .flat_map(|p| split_paragraph(p, max_len))
+        .fold(vec![], |mut acc: Vec<String>, p| {
+            match acc.last_mut() {
+                Some(last) if text_len(last) + 2 + text_len(&p) <= max_len => {
+                    last.push_str("\n\n");
+                    last.push_str(&p);
+                }
+                _ => acc.push(p),
+            }
+            acc
+        })
+}
+
+// at the end of a sentence if there's one in the second half of a message, otherwise between words,
+// never inside a tag or an entity. tags still open at a split are closed and reopened in the next message
+fn split_paragraph(html: &str, max_len: usize) -> Vec<String> {
+    let tokens = words(tokens(html), max_len);
+    let mut chunks = vec![];
+    let mut open = vec![];
+    let mut start = 0;
+    while start < tokens.len() {
+        let end = chunk_end(&tokens, start, max_len);
+        let prefix = open.iter().map(|(_, raw)| *raw).collect::<String>();
+        let mut body = String::new();
+        for token in &tokens[start..end] {
+            match token {
+                Token::Open(name, raw) => open.push((*name, *raw)),
+                Token::Close(_) => {
+                    open.pop();
+                }
+                _ => {}
+            }
+            body.push_str(token.raw());
+        }
+        let closing = open
+            .iter()
+            .rev()
+            .map(|(name, _)| format!("</{}>", name))
+            .collect::<String>();
+        let chunk = format!("{}{}{}", prefix, body.trim(), closing);
+        if text_len(&chunk) > 0 {
+            chunks.push(chunk);
+        }
+        start = end;
+    }
+    chunks
+}
+
+// tokens[start..end] fit into max_len
+fn chunk_end(tokens: &[Token], start: usize, max_len: usize) -> usize {
+    let mut len = 0;
+    let mut sentence_end = None;
+    for (i, token) in tokens.iter().enumerate().skip(start) {
+        if len + token.len() > max_len {
+            let end = match sentence_end {
+                Some(end) => end,
+                // an opening tag goes to the next message along with the text it's opened for
+                None => {
+                    let mut end = i;
+                    while end > start + 1 && matches!(tokens[end - 1], Token::Open(..)) {
+                        end -= 1;
+                    }
+                    end
+                }
+            };
+            // a single char is always shorter than the limit, but just in case
+            return end.max(start + 1);
+        }
+        len += token.len();
+        if token.is_sentence_end() && len >= max_len / 2 {
+            sentence_end = Some(i + 1);
+        }
+    }
+    tokens.len()
+}
+
+// text tokens broken into words with their trailing whitespace, and words too long for a message into chars
+fn words(tokens: Vec<Token>, max_len: usize) -> Vec<Token> {
+    tokens
+        .into_iter()
+        .flat_map(|token| match token {
+            Token::Text(t) => t
+                .split_inclusive(char::is_whitespace)
+                .flat_map(|word| match utf16_len(word) <= max_len / 2 {
+                    true => vec![Token::Text(word)],
+                    false => word
+                        .char_indices()
+                        .map(|(i, c)| Token::Text(&word[i..i + c.len_utf8()]))
+                        .collect(),
+                })
+                .collect::<Vec<_>>(),
+            token => vec![token],
+        })
+        .collect()
+}
+
+enum Token<'a> {
+    // tag name and the whole tag
+    Open(&'a str, &'a str),
+    Close(&'a str),
+    Entity(&'a str),
+    Text(&'a str),
+}
+
+impl<'a> Token<'a> {
+    fn raw(&self) -> &'a str {
+        match self {
+            Token::Open(_, raw) | Token::Close(raw) | Token::Entity(raw) | Token::Text(raw) => raw,
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Token::Open(..) | Token::Close(_) => 0,
+            Token::Entity(_) => 1,
+            Token::Text(t) => utf16_len(t),
+        }
+    }
+
+    // words are followed by whitespace, so a sentence is over after a word like `end. `
+    fn is_sentence_end(&self) -> bool {
+        match self {
+            Token::Text(t) => {
+                t.ends_with('\n')
+                    || t.trim_end().len() < t.len() && t.trim_end().ends_with(['.', '!', '?', '…'])
+            }
+            _ => false,
+        }
+    }
+}
+
+fn tokens(html: &str) -> Vec<Token<'_>> {
+    let mut tokens = vec![];
+    let mut rest = html;
+    while !rest.is_empty() {
+        let (token, len) = match rest.as_bytes()[0] {
+            b'<' => match rest.find('>') {
+                Some(end) if rest.starts_with("</") => (Token::Close(&rest[..=end]), end + 1),
+                Some(end) => {
+                    let name = rest[1..end]
+                        .split(|c: char| c.is_whitespace() || c == '/')
+                        .next()
+                        .unwrap_or_default();
+                    (Token::Open(name, &rest[..=end]), end + 1)
+                }
+                None => (Token::Text(&rest[..1]), 1),
+            },
+            b'&' => match rest.find(';').filter(|end| {
+                *end > 1
+                    && *end < 10
+                    && rest[1..*end]
+                        .chars()
+                        .all(|c| c.is_alphanumeric() || c == '#')
+            }) {
+                Some(end) => (Token::Entity(&rest[..=end]), end + 1),
+                None => (Token::Text(&rest[..1]), 1),
+            },
+            _ => {
+                let end = rest.find(['<', '&']).unwrap_or(rest.len());
+                (Token::Text(&rest[..end]), end)
+            }
+        };
+        tokens.push(token);
+        rest = &rest[len..];
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paragraphs(ps: &[&str]) -> Vec<String> {
+        ps.iter().map(|p| p.to_string()).collect()
+    }
+
+    #[test]
+    fn counts_utf16_code_units() {
+        assert_eq!(utf16_len("abc"), 3);
+        assert_eq!(utf16_len("привет"), 6);
+        // outside of the basic plane, a surrogate pair each
+        assert_eq!(utf16_len("😈👹"), 4);
+        assert_eq!(text_len("<b>при</b>&amp;<a href=\"x\">😈</a>"), 6);
+    }
+
+    #[test]
+    fn merges_short_paragraphs() {
+        let split = split(&paragraphs(&["one", "two", "three"]), 20);
+        assert_eq!(split, vec!["one\n\ntwo\n\nthree"]);
+    }
+
+    #[test]
+    fn keeps_paragraphs_whole_when_they_fit() {
+        let split = split(&paragraphs(&["aaaa bbbb", "cccc dddd"]), 12);
+        assert_eq!(split, vec!["aaaa bbbb", "cccc dddd"]);
+    }
+
+    #[test]
+    fn splits_huge_paragraph_within_limit() {
+        let sentence = "Сорок два демона пришли. ".repeat(500);
+        let split = split(std::slice::from_ref(&sentence), MAX_TEXT_LEN);
+        assert!(split.len() > 1);
+        assert!(split.iter().all(|s| text_len(s) <= MAX_TEXT_LEN));
+        assert_eq!(
+            split.join(" ").split_whitespace().count(),
+            sentence.split_whitespace().count()
+        );
+    }
+
+    #[test]
+    fn prefers_sentence_ends() {
+        let split = split(&paragraphs(&["First one. Second one goes on"]), 20);
+        assert_eq!(split, vec!["First one.", "Second one goes on"]);
+    }
+
+    #[test]
+    fn splits_words_when_there_are_no_sentences() {
+        let split = split(&paragraphs(&["aaa bbb ccc ddd"]), 8);
+        assert_eq!(split, vec!["aaa bbb", "ccc ddd"]);
+    }
+
+    #[test]
+    fn never_cuts_emoji_in_half() {
+        let emojis = "😈".repeat(3000);
+        let split = split(&[emojis], MAX_CAPTION_LEN);
+        assert!(split.iter().all(|s| utf16_len(s) <= MAX_CAPTION_LEN));
+        assert_eq!(split.concat(), "😈".repeat(3000));
+    }
+
+    #[test]
+    fn reopens_tags_split_across_messages() {
+        let split = split(&paragraphs(&["<b>aaa <i>bbb ccc</i> ddd</b>"]), 8);
+        assert_eq!(
+            split,
+            vec!["<b>aaa <i>bbb</i></b>", "<b><i>ccc</i> ddd</b>"]
+        );
+    }
+
+    #[test]
+    fn never_splits_entities() {
+        let split = split(&paragraphs(&["&amp;&amp;&amp;&amp;"]), 2);
+        assert_eq!(split, vec!["&amp;&amp;", "&amp;&amp;"]);
+    }
+
+    #[test]
+    fn drops_paragraphs_with_nothing_to_show() {
+        assert!(split(&paragraphs(&["<a href=\"x\"></a>"]), MAX_TEXT_LEN).is_empty());
+        let split = split(&paragraphs(&["<a href=\"x\"></a>", "text"]), MAX_TEXT_LEN);
+        assert_eq!(split, vec!["text"]);
+    }
+
+    #[test]
+    fn strips_tags() {
+        assert_eq!(strip_tags("<b>a &lt;3</b> <a href=\"x\">b</a>"), "a <3 b");
+    }
+
+    #[test]
+    fn truncates_to_utf16_len() {
+        assert_eq!(truncate("abc", 3), "abc");
+        assert_eq!(truncate("😈😈😈", 5), "😈😈…");
+    }
+}
@@ -0,0 +1,377 @@
+// the message catalog, every piece of text the bot says to people lives here
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Lang {
+    En,
+    Ru,
+}
+
+impl Lang {
+    pub const ALL: [Lang; 2] = [Lang::En, Lang::Ru];
+
+    // telegram gives language codes like `en` or `pt-br`, anything unknown falls back to english
+    pub fn from_code(code: Option<&str>) -> Lang {
+        match code.and_then(|c| c.split('-').next()) {
+            Some("ru") => Lang::Ru,
+            _ => Lang::En,
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Lang::En => "en",
+            Lang::Ru => "ru",
+        }
+    }
+}
+
+pub enum Msg {
+    GreatNews,
+    NoFirstPage,
+    NoLastPage,
+    NoPages,
+    NothingOnThisDay,
+    NoPageAt(usize),
+    JumpTo,
+    First,
+    Last,
+    Prev,
+    Next,
+    Bookmark,
+    Share,
+    Translate,
+    Translation,
+    Open,
+    OnSite,
+    Bookmarks,
+    NoBookmarks,
+    Bookmarked(usize),
+    BookmarkedAlready(usize),
+    Remove,
+    Notes,
+    NoNotes,
+    NoteUsage,
+    OpenPageFirst,
+    NoteAdded(usize),
+    PageNotes(usize),
+    RecentPages,
+    NoHistory,
+    BackTo(usize),
+    Settings,
+    SetImages,
+    SetTranslateTo,
+    SetNewPages,
+    SetQuietHours,
+    SetReaderMode,
+    SetLanguage,
+    SetQuietMode,
+    SetTimezone,
+    Silent,
+    Queue,
+    TimezoneUsage,
+    TimezoneSet(i32),
+    SetDigest,
+    SetDigestHour,
+    SetReplies,
+    SetPinLatest,
+    SetCleanup,
+    SetCompact,
+    KeepPages(usize),
+    AfterHours(i64),
+    Cleaned(usize),
+    InChat,
+    InPrivate,
+    Daily,
+    Weekly,
+    Digest(usize),
+    ReadFromHere,
+    NewPages(usize, usize),
+    StartReading,
+    RereadUsage,
+    RereadFrom(usize, usize, u32),
+    RereadPaused(usize),
+    NothingToPause,
+    ClubUsage,
+    GroupsOnly,
+    AdminsOnly,
+    ClubStarted(usize, usize, usize, u32),
+    ClubStopped,
+    NoClub,
+    ClubProgress(usize, usize, usize),
+    ReadyForNext(usize, usize),
+    VoteCounted,
+    VoteStale,
+    On,
+    Off,
+    Files,
+    Photos,
+    HelpHeader,
+    CmdFirst,
+    CmdLast,
+    CmdJump,
+    CmdRandom,
+    CmdOnThisDay,
+    CmdBookmarks,
+    CmdNote,
+    CmdNotes,
+    CmdHistory,
+    CmdSettings,
+    CmdTimezone,
+    CmdReread,
+    CmdPause,
+    CmdClub,
+    CmdClean,
+    CmdHelp,
+}
+
+impl Msg {
+    pub fn text(&self, lang: Lang) -> String {
+        match lang {
+            Lang::En => self.en(),
+            Lang::Ru => self.ru(),
+        }
+    }
+
+    fn en(&self) -> String {
+        match self {
+            Msg::GreatNews => "🎉🎉🎉 GREAT NEWS!! NEW PAGE IS ON THE WAY 🎉🎉🎉".to_string(),
+            Msg::NoFirstPage => ":( no first page".to_string(),
+            Msg::NoLastPage => ":( no last page".to_string(),
+            Msg::NoPages => ":( no pages at all".to_string(),
+            Msg::NothingOnThisDay => ":( nothing published on this day".to_string(),
+            Msg::NoPageAt(idx) => format!(":( no page at idx {}", idx),
+            Msg::JumpTo => "JUMP TO".to_string(),
+            Msg::First => "FIRST".to_string(),
+            Msg::Last => "LAST".to_string(),
+            Msg::Prev => "PREV".to_string(),
+            Msg::Next => "NEXT".to_string(),
+            Msg::Bookmark => "BOOKMARK".to_string(),
+            Msg::Share => "SHARE".to_string(),
+            Msg::Translate => "TRANSLATE".to_string(),
+            Msg::Translation => "TRANSLATION".to_string(),
+            Msg::Open => "OPEN".to_string(),
+            Msg::OnSite => "ON SITE".to_string(),
+            Msg::Bookmarks => "BOOKMARKS".to_string(),
+            Msg::NoBookmarks => ":( no bookmarks yet, use BOOKMARK button under a page".to_string(),
+            Msg::Bookmarked(idx) => format!("page {} bookmarked", idx),
+            Msg::BookmarkedAlready(idx) => format!("page {} is bookmarked already", idx),
+            Msg::Remove => "REMOVE".to_string(),
+            Msg::Notes => "📝 NOTES".to_string(),
+            Msg::NoNotes => ":( no notes yet".to_string(),
+            Msg::NoteUsage => ":( usage: /note <text> while on a page".to_string(),
+            Msg::OpenPageFirst => ":( open a page first".to_string(),
+            Msg::NoteAdded(idx) => format!("📝 note added to page {}", idx),
+            Msg::PageNotes(idx) => format!("page {}:", idx),
+            Msg::RecentPages => "RECENT PAGES".to_string(),
+            Msg::NoHistory => ":( no pages opened yet".to_string(),
+            Msg::BackTo(idx) => format!("BACK TO #{}", idx),
+            Msg::Settings => "SETTINGS".to_string(),
+            Msg::SetImages => "Images".to_string(),
+            Msg::SetTranslateTo => "Translate to".to_string(),
+            Msg::SetNewPages => "New pages".to_string(),
+            Msg::SetQuietHours => "Quiet hours".to_string(),
+            Msg::SetReaderMode => "Reader mode".to_string(),
+            Msg::SetLanguage => "Language".to_string(),
+            Msg::SetQuietMode => "During quiet hours".to_string(),
+            Msg::SetTimezone => "Timezone".to_string(),
+            Msg::Silent => "send silently".to_string(),
+            Msg::Queue => "wait till morning".to_string(),
+            Msg::TimezoneUsage => ":( usage: /timezone <utc offset from -12 to +14>".to_string(),
+            Msg::TimezoneSet(offset) => format!("timezone is UTC{:+} now", offset),
+            Msg::SetDigest => "Digest".to_string(),
+            Msg::SetDigestHour => "Digest at".to_string(),
+            Msg::SetReplies => "Replies".to_string(),
+            Msg::SetPinLatest => "Pin the latest page".to_string(),
+            Msg::SetCleanup => "Clean up pages".to_string(),
+            Msg::SetCompact => "Compact pages".to_string(),
+            Msg::KeepPages(n) => format!("keep last {}", n),
+            Msg::AfterHours(h) => format!("after {}h", h),
+            Msg::Cleaned(n) => format!("🧹 {} messages removed", n),
+            Msg::InChat => "in the group".to_string(),
+            Msg::InPrivate => "in private".to_string(),
+            Msg::Daily => "daily".to_string(),
+            Msg::Weekly => "weekly, on mondays".to_string(),
+            Msg::Digest(n) => format!("📬 {} new pages since the last digest", n),
+            Msg::ReadFromHere => "READ FROM HERE".to_string(),
+            Msg::NewPages(count, idx) => {
+                format!("🎉🎉🎉 {} new pages, starting at #{} 🎉🎉🎉", count, idx)
+            }
+            Msg::StartReading => "START READING".to_string(),
+            Msg::RereadUsage => {
+                ":( usage: /reread [from page] [pages per day, up to 10]".to_string()
+            }
+            Msg::RereadFrom(idx, per_day, hour) => format!(
+                "rereading from #{}, {} page(s) a day at {}:00. /pause to take a break",
+                idx, per_day, hour
+            ),
+            Msg::RereadPaused(idx) => format!("paused before #{}, /reread to go on", idx),
+            Msg::NothingToPause => "nothing to pause, start with /reread".to_string(),
+            Msg::ClubUsage => {
+                ":( usage: /club [from page] [pages per round, up to 10], or /club off".to_string()
+            }
+            Msg::GroupsOnly => ":( book clubs are for groups".to_string(),
+            Msg::AdminsOnly => ":( only group admins can do that".to_string(),
+            Msg::ClubStarted(idx, per_round, quorum, hour) => format!(
+                "📚 book club starts from #{}: {} page(s) a round, at {}:00 once {} member(s) are ready",
+                idx, per_round, hour, quorum
+            ),
+            Msg::ClubStopped => "📚 book club is over".to_string(),
+            Msg::NoClub => ":( there is no book club here, start one with /club".to_string(),
+            Msg::ClubProgress(idx, last, percent) => {
+                format!("📚 the club has read up to #{} of #{} ({}%)", idx, last, percent)
+            }
+            Msg::ReadyForNext(votes, quorum) => {
+                format!("READY FOR NEXT ({}/{})", votes, quorum)
+            }
+            Msg::VoteCounted => "got it, you're ready".to_string(),
+            Msg::VoteStale => "this round is over".to_string(),
+            Msg::On => "on".to_string(),
+            Msg::Off => "off".to_string(),
+            Msg::Files => "files".to_string(),
+            Msg::Photos => "photos".to_string(),
+            Msg::HelpHeader => "These commands are supported:".to_string(),
+            Msg::CmdFirst => "first page".to_string(),
+            Msg::CmdLast => "last available page".to_string(),
+            Msg::CmdJump => "jump to some page".to_string(),
+            Msg::CmdRandom => "random page".to_string(),
+            Msg::CmdOnThisDay => "pages published on this day".to_string(),
+            Msg::CmdBookmarks => "bookmarked pages".to_string(),
+            Msg::CmdNote => "add a note to the current page".to_string(),
+            Msg::CmdNotes => "all your notes".to_string(),
+            Msg::CmdHistory => "recently opened pages".to_string(),
+            Msg::CmdSettings => "chat settings".to_string(),
+            Msg::CmdTimezone => "set timezone, e.g. /timezone +3".to_string(),
+            Msg::CmdReread => "reread the archive a page a day, e.g. /reread 1 2".to_string(),
+            Msg::CmdPause => "pause rereading".to_string(),
+            Msg::CmdClub => "start a book club in a group, e.g. /club 1 3".to_string(),
+            Msg::CmdClean => "remove sent pages from the chat".to_string(),
+            Msg::CmdHelp => "available commands".to_string(),
+        }
+    }
+
+    fn ru(&self) -> String {
+        match self {
+            Msg::GreatNews => {
+                "🎉🎉🎉 ОТЛИЧНЫЕ НОВОСТИ!! НОВАЯ СТРАНИЦА УЖЕ В ПУТИ 🎉🎉🎉".to_string()
+            }
+            Msg::NoFirstPage => ":( первой страницы нет".to_string(),
+            Msg::NoLastPage => ":( последней страницы нет".to_string(),
+            Msg::NoPages => ":( страниц нет совсем".to_string(),
+            Msg::NothingOnThisDay => ":( в этот день ничего не публиковалось".to_string(),
+            Msg::NoPageAt(idx) => format!(":( нет страницы под номером {}", idx),
+            Msg::JumpTo => "ПЕРЕЙТИ К".to_string(),
+            Msg::First => "ПЕРВАЯ".to_string(),
+            Msg::Last => "ПОСЛЕДНЯЯ".to_string(),
+            Msg::Prev => "НАЗАД".to_string(),
+            Msg::Next => "ВПЕРЁД".to_string(),
+            Msg::Bookmark => "В ЗАКЛАДКИ".to_string(),
+            Msg::Share => "ПОДЕЛИТЬСЯ".to_string(),
+            Msg::Translate => "ПЕРЕВЕСТИ".to_string(),
+            Msg::Translation => "ПЕРЕВОД".to_string(),
+            Msg::Open => "ОТКРЫТЬ".to_string(),
+            Msg::OnSite => "НА САЙТЕ".to_string(),
+            Msg::Bookmarks => "ЗАКЛАДКИ".to_string(),
+            Msg::NoBookmarks => {
+                ":( закладок пока нет, используйте кнопку В ЗАКЛАДКИ под страницей".to_string()
+            }
+            Msg::Bookmarked(idx) => format!("страница {} в закладках", idx),
+            Msg::BookmarkedAlready(idx) => format!("страница {} уже в закладках", idx),
+            Msg::Remove => "УДАЛИТЬ".to_string(),
+            Msg::Notes => "📝 ЗАМЕТКИ".to_string(),
+            Msg::NoNotes => ":( заметок пока нет".to_string(),
+            Msg::NoteUsage => ":( использование: /note <текст>, находясь на странице".to_string(),
+            Msg::OpenPageFirst => ":( сначала откройте страницу".to_string(),
+            Msg::NoteAdded(idx) => format!("📝 заметка добавлена к странице {}", idx),
+            Msg::PageNotes(idx) => format!("страница {}:", idx),
+            Msg::RecentPages => "НЕДАВНИЕ СТРАНИЦЫ".to_string(),
+            Msg::NoHistory => ":( вы ещё не открывали страниц".to_string(),
+            Msg::BackTo(idx) => format!("НАЗАД К #{}", idx),
+            Msg::Settings => "НАСТРОЙКИ".to_string(),
+            Msg::SetImages => "Картинки".to_string(),
+            Msg::SetTranslateTo => "Переводить на".to_string(),
+            Msg::SetNewPages => "Новые страницы".to_string(),
+            Msg::SetQuietHours => "Тихие часы".to_string(),
+            Msg::SetReaderMode => "Режим чтения".to_string(),
+            Msg::SetLanguage => "Язык".to_string(),
+            Msg::SetQuietMode => "В тихие часы".to_string(),
+            Msg::SetTimezone => "Часовой пояс".to_string(),
+            Msg::Silent => "присылать без звука".to_string(),
+            Msg::Queue => "ждать до утра".to_string(),
+            Msg::TimezoneUsage => {
+                ":( использование: /timezone <смещение от UTC, от -12 до +14>".to_string()
+            }
+            Msg::TimezoneSet(offset) => format!("часовой пояс теперь UTC{:+}", offset),
+            Msg::SetDigest => "Дайджест".to_string(),
+            Msg::SetDigestHour => "Дайджест в".to_string(),
+            Msg::SetReplies => "Ответы".to_string(),
+            Msg::SetPinLatest => "Закреплять новую страницу".to_string(),
+            Msg::SetCleanup => "Удалять страницы".to_string(),
+            Msg::SetCompact => "Компактные страницы".to_string(),
+            Msg::KeepPages(n) => format!("оставлять последние {}", n),
+            Msg::AfterHours(h) => format!("через {} ч", h),
+            Msg::Cleaned(n) => format!("🧹 удалено сообщений: {}", n),
+            Msg::InChat => "в группе".to_string(),
+            Msg::InPrivate => "в личке".to_string(),
+            Msg::Daily => "ежедневно".to_string(),
+            Msg::Weekly => "еженедельно, по понедельникам".to_string(),
+            Msg::Digest(n) => format!("📬 новых страниц с прошлого дайджеста: {}", n),
+            Msg::ReadFromHere => "ЧИТАТЬ ОТСЮДА".to_string(),
+            Msg::NewPages(count, idx) => {
+                format!("🎉🎉🎉 новых страниц: {}, начиная с #{} 🎉🎉🎉", count, idx)
+            }
+            Msg::StartReading => "НАЧАТЬ ЧИТАТЬ".to_string(),
+            Msg::RereadUsage => {
+                ":( использование: /reread [с какой страницы] [страниц в день, до 10]".to_string()
+            }
+            Msg::RereadFrom(idx, per_day, hour) => format!(
+                "перечитываем с #{}, страниц в день: {}, в {}:00. /pause, чтобы сделать перерыв",
+                idx, per_day, hour
+            ),
+            Msg::RereadPaused(idx) => {
+                format!("пауза перед #{}, /reread, чтобы продолжить", idx)
+            }
+            Msg::NothingToPause => "нечего ставить на паузу, начните с /reread".to_string(),
+            Msg::ClubUsage => {
+                ":( использование: /club [с какой страницы] [страниц за раз, до 10] или /club off"
+                    .to_string()
+            }
+            Msg::GroupsOnly => ":( книжные клубы только для групп".to_string(),
+            Msg::AdminsOnly => ":( это могут только админы группы".to_string(),
+            Msg::ClubStarted(idx, per_round, quorum, hour) => format!(
+                "📚 книжный клуб начинает с #{}: страниц за раз: {}, в {}:00, когда готовы участников: {}",
+                idx, per_round, hour, quorum
+            ),
+            Msg::ClubStopped => "📚 книжный клуб закрыт".to_string(),
+            Msg::NoClub => ":( здесь нет книжного клуба, начните его с /club".to_string(),
+            Msg::ClubProgress(idx, last, percent) => {
+                format!("📚 клуб прочитал до #{} из #{} ({}%)", idx, last, percent)
+            }
+            Msg::ReadyForNext(votes, quorum) => format!("ГОТОВЫ ДАЛЬШЕ ({}/{})", votes, quorum),
+            Msg::VoteCounted => "принято, вы готовы".to_string(),
+            Msg::VoteStale => "этот раунд уже закончился".to_string(),
+            Msg::On => "вкл".to_string(),
+            Msg::Off => "выкл".to_string(),
+            Msg::Files => "файлы".to_string(),
+            Msg::Photos => "фото".to_string(),
+            Msg::HelpHeader => "Доступные команды:".to_string(),
+            Msg::CmdFirst => "первая страница".to_string(),
+            Msg::CmdLast => "последняя страница".to_string(),
+            Msg::CmdJump => "перейти к странице".to_string(),
+            Msg::CmdRandom => "случайная страница".to_string(),
+            Msg::CmdOnThisDay => "страницы, опубликованные в этот день".to_string(),
+            Msg::CmdBookmarks => "закладки".to_string(),
+            Msg::CmdNote => "заметка к текущей странице".to_string(),
+            Msg::CmdNotes => "все ваши заметки".to_string(),
+            Msg::CmdHistory => "недавно открытые страницы".to_string(),
+            Msg::CmdSettings => "настройки чата".to_string(),
+            Msg::CmdTimezone => "часовой пояс, например /timezone +3".to_string(),
+            Msg::CmdReread => {
+                "перечитывать архив по странице в день, например /reread 1 2".to_string()
+            }
+            Msg::CmdPause => "приостановить перечитывание".to_string(),
+            Msg::CmdClub => "книжный клуб в группе, например /club 1 3".to_string(),
+            Msg::CmdClean => "удалить отправленные страницы из чата".to_string(),
+            Msg::CmdHelp => "доступные команды".to_string(),
+        }
+    }
+}
@@ -1,5 +1,7 @@
 use std::fmt::{Display, Formatter};
 
+use chrono::{DateTime, Utc};
+
 #[derive(Debug, Clone)]
 pub struct KsbdPage {
     pub idx: usize,
@@ -8,6 +10,7 @@ pub struct KsbdPage {
     pub imgs: Vec<String>,
     pub text: String,
     pub next: Option<String>,
+    pub published: Option<DateTime<Utc>>,
 }
 
 impl Display for KsbdPage {
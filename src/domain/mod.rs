@@ -1,7 +1,21 @@
+pub mod bookmarks_state;
 pub mod bot_cmd;
 pub mod bot_state;
+pub mod channels_state;
+pub mod club_state;
+pub mod file_ids_state;
+pub mod history_state;
+pub mod html_text;
+pub mod i18n;
 pub mod ksbd_page;
 pub mod ksbd_page_error;
+pub mod notes_state;
 pub mod page_to_send;
 pub mod pages_state;
+pub mod pins_state;
+pub mod queue_state;
+pub mod reread_state;
+pub mod sent_state;
+pub mod settings_state;
 pub mod subs_state;
+pub mod translate_error;
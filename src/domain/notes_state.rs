@@ -0,0 +1,93 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use teloxide::prelude::ChatId;
+
+// private notes chats attach to pages, stored with the same escaping as page texts
+#[derive(Debug, Default, Clone)]
+pub struct NotesState {
+    notes: HashMap<i64, BTreeMap<usize, Vec<String>>>,
+}
+
+impl NotesState {
+    pub fn add(&mut self, chat_id: ChatId, idx: usize, note: String) {
+        self.notes
+            .entry(chat_id.0)
+            .or_default()
+            .entry(idx)
+            .or_default()
+            .push(note);
+    }
+
+    pub fn by_page(&self, chat_id: ChatId, idx: usize) -> Vec<String> {
+        self.notes
+            .get(&chat_id.0)
+            .and_then(|pages| pages.get(&idx))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn by_chat(&self, chat_id: ChatId) -> Vec<(usize, Vec<String>)> {
+        self.notes
+            .get(&chat_id.0)
+            .map(|pages| {
+                pages
+                    .iter()
+                    .map(|(idx, notes)| (*idx, notes.clone()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn migrate(&mut self, from: ChatId, to: ChatId) -> bool {
+        match self.notes.remove(&from.0) {
+            Some(notes) => {
+                self.notes.insert(to.0, notes);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl FromStr for NotesState {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut state = NotesState::default();
+        s.split('\n').filter(|l| !l.is_empty()).for_each(|l| {
+            let l_split = l.split('\t').collect::<Vec<_>>();
+            state.add(
+                ChatId(l_split[0].parse::<i64>().unwrap()),
+                l_split[1].parse::<usize>().unwrap(),
+                l_split[2].replace("%09", "\t").replace("%0D%0A", "\n"),
+            );
+        });
+
+        Ok(state)
+    }
+}
+
+impl Display for NotesState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let lines = self
+            .notes
+            .iter()
+            .flat_map(|(uid, pages)| {
+                pages.iter().flat_map(move |(idx, notes)| {
+                    notes.iter().map(move |n| {
+                        format!(
+                            "{}\t{}\t{}",
+                            uid,
+                            idx,
+                            n.replace('\t', "%09").replace('\n', "%0D%0A")
+                        )
+                    })
+                })
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        write!(f, "{}", lines)
+    }
+}
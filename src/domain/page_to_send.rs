@@ -1,14 +1,26 @@
+use chrono::{DateTime, Utc};
+
 use crate::cfg::DATA_PATH;
 use crate::domain::ksbd_page::KsbdPage;
 
+// telegram takes 2 to 10 images in an album
+static MAX_ALBUM_LEN: usize = 10;
+
 #[derive(Debug)]
 pub struct PageToSend {
     pub idx: usize,
+    pub url: String,
     pub title: Option<String>,
     pub imgs: Vec<String>,
     pub text: Vec<String>,
     pub is_new: bool,
     pub has_next: bool,
+    pub published: Option<DateTime<Utc>>,
+    pub has_notes: bool,
+    // sent without a sound, e.g. during chat's quiet hours
+    pub is_silent: bool,
+    // forum topic the page goes to, if any
+    pub thread_id: Option<i32>,
 }
 
 impl PageToSend {
@@ -20,19 +32,18 @@ impl PageToSend {
             .trim()
             .to_string();
 
+        // paragraphs of html, line breaks are kept inside them
         let text_blocks = p
             .text
             .replace("%09", "\t")
-            .split("%0D%0A")
-            .map(|s| s.trim().to_string())
+            .split("%0D%0A%0D%0A")
+            .map(|s| s.replace("%0D%0A", "\n").trim().to_string())
             .filter(|s| !s.is_empty())
             .collect::<Vec<_>>();
-        // .replace("%0D%0A", "\n")
-        // .trim()
-        // .to_string();
 
         PageToSend {
             idx: p.idx,
+            url: p.url.clone(),
             title: if raw_title.is_empty() {
                 None
             } else {
@@ -42,6 +53,10 @@ impl PageToSend {
             text: text_blocks,
             is_new,
             has_next: p.next.is_some(),
+            published: p.published,
+            has_notes: false,
+            is_silent: false,
+            thread_id: None,
         }
     }
 
@@ -53,6 +68,26 @@ impl PageToSend {
         PageToSend::new(p, false)
     }
 
+    // title with publication date underneath, whatever of them is known
+    pub fn header(&self) -> Option<String> {
+        let published = self
+            .published
+            .map(|d| format!("📅 {}", d.format("%Y-%m-%d")));
+
+        match (&self.title, published) {
+            (Some(title), Some(published)) => Some(format!("{}\n{}", title, published)),
+            (title, published) => title.clone().or(published),
+        }
+    }
+
+    // urls look like .../comic/wielder-of-names-4-1/, the slug without the page number names the chapter
+    pub fn chapter_tag(&self) -> Option<String> {
+        let slug = self.url.trim_end_matches('/').rsplit('/').next()?;
+        let (chapter, page) = slug.rsplit_once('-')?;
+        page.parse::<usize>().ok()?;
+        Some(format!("#{}", chapter.replace('-', "_")))
+    }
+
     pub fn img_files(&self) -> Vec<String> {
         self.imgs
             .iter()
@@ -60,4 +95,16 @@ impl PageToSend {
             .map(|(img_idx, _)| format!("{}/{}-{}.png", DATA_PATH.as_str(), self.idx, img_idx))
             .collect::<Vec<_>>()
     }
+
+    // images as channels get them, a page without any is a single text message
+    pub fn albums(&self) -> Vec<Vec<String>> {
+        self.img_files()
+            .chunks(MAX_ALBUM_LEN)
+            .map(<[String]>::to_vec)
+            .collect()
+    }
+
+    pub fn album_count(&self) -> usize {
+        self.albums().len().max(1)
+    }
 }
@@ -1,12 +1,21 @@
+use crate::domain::html_text::strip_tags;
 use crate::domain::ksbd_page::KsbdPage;
+use chrono::{DateTime, Datelike, Utc};
+use rand::seq::SliceRandom;
+use std::collections::BTreeSet;
+use std::fmt::{Display, Formatter};
 use std::str::FromStr;
-use std::string::ToString;
 
 #[derive(Debug, Default, Clone)]
 pub struct PagesState {
     pages: Vec<KsbdPage>,
+    // pages the site gives no publication date for, not worth asking again
+    undated: BTreeSet<usize>,
 }
 
+// the publication date column of an undated page
+const UNDATED: &str = "UNKNOWN";
+
 const INITIAL_PAGE: &str =
     "https://killsixbilliondemons.com/comic/kill-six-billion-demons-chapter-1/";
 
@@ -25,12 +34,8 @@ impl PagesState {
     pub fn add_page(&mut self, page: KsbdPage) {
         if let Some(p) = self.pages.pop() {
             let prev_last = KsbdPage {
-                idx: p.idx,
-                title: p.title,
-                url: p.url,
-                imgs: p.imgs,
-                text: p.text,
                 next: Some(page.url.clone()),
+                ..p
             };
 
             self.pages.push(prev_last);
@@ -46,7 +51,7 @@ impl PagesState {
     }
 
     pub fn first(&self) -> Option<&KsbdPage> {
-        self.pages.get(0)
+        self.pages.first()
     }
 
     pub fn last(&self) -> Option<&KsbdPage> {
@@ -56,14 +61,78 @@ impl PagesState {
     pub fn by_idx(&self, idx: usize) -> Option<&KsbdPage> {
         self.pages.get(idx)
     }
+
+    // pages scraped before publication dates were a thing
+    pub fn without_published(&self) -> Vec<(usize, String)> {
+        self.pages
+            .iter()
+            .filter(|p| p.published.is_none() && !self.undated.contains(&p.idx))
+            .map(|p| (p.idx, p.url.clone()))
+            .collect()
+    }
+
+    // no date means the page has none to find
+    pub fn set_published(&mut self, idx: usize, published: Option<DateTime<Utc>>) {
+        match (self.pages.get_mut(idx), published) {
+            (Some(p), Some(published)) => p.published = Some(published),
+            (Some(_), None) => {
+                self.undated.insert(idx);
+            }
+            (None, _) => {}
+        }
+    }
+
+    // pages published on the same day and month as `today`, but in previous years
+    pub fn on_this_day(&self, today: DateTime<Utc>) -> Vec<&KsbdPage> {
+        self.pages
+            .iter()
+            .filter(|p| {
+                p.published.is_some_and(|d| {
+                    d.year() < today.year() && d.month() == today.month() && d.day() == today.day()
+                })
+            })
+            .collect()
+    }
+
+    pub fn random(&self) -> Option<&KsbdPage> {
+        self.pages.choose(&mut rand::thread_rng())
+    }
+
+    // a number is treated as a page idx, anything else as words to look up in titles and texts.
+    // empty query gives the most recent pages
+    pub fn search(&self, query: &str, limit: usize) -> Vec<&KsbdPage> {
+        let query = query.trim().to_lowercase();
+        if let Ok(idx) = query.parse::<usize>() {
+            return self.by_idx(idx).into_iter().collect();
+        }
+
+        let words = query.split_whitespace().collect::<Vec<_>>();
+        self.pages
+            .iter()
+            .rev()
+            .filter(|p| {
+                let title = p.title.to_lowercase();
+                let text = strip_tags(&p.text).to_lowercase();
+                words.iter().all(|w| title.contains(w) || text.contains(w))
+            })
+            .take(limit)
+            .collect()
+    }
 }
 
 impl FromStr for PagesState {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let pages = s
-            .split('\n')
+        let rows = s.split('\n').collect::<Vec<_>>();
+        let undated = rows
+            .iter()
+            .enumerate()
+            .filter(|(_, l)| l.split('\t').nth(5) == Some(UNDATED))
+            .map(|(idx, _)| idx)
+            .collect::<BTreeSet<_>>();
+        let pages = rows
+            .into_iter()
             .enumerate()
             .map(|(idx, l)| {
                 let l_split = l.split('\t').collect::<Vec<_>>();
@@ -81,29 +150,41 @@ impl FromStr for PagesState {
                     } else {
                         Some(l_split[4].to_string())
                     },
+                    // older states have no publication date column at all
+                    published: l_split
+                        .get(5)
+                        .and_then(|d| DateTime::parse_from_rfc3339(d).ok())
+                        .map(|d| d.with_timezone(&Utc)),
                 }
             })
             .collect::<Vec<_>>();
 
-        Ok(PagesState { pages })
+        Ok(PagesState { pages, undated })
     }
 }
 
-impl ToString for PagesState {
-    fn to_string(&self) -> String {
-        self.pages
+impl Display for PagesState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let lines = self
+            .pages
             .iter()
             .map(|p| {
                 format!(
-                    "{}\t{}\t{}\t{}\t{}",
+                    "{}\t{}\t{}\t{}\t{}\t{}",
                     p.title,
                     p.url,
                     p.imgs.join("|"),
                     p.text,
-                    p.next.clone().unwrap_or("NO".to_string())
+                    p.next.clone().unwrap_or("NO".to_string()),
+                    match (p.published, self.undated.contains(&p.idx)) {
+                        (Some(d), _) => d.to_rfc3339(),
+                        (None, true) => UNDATED.to_string(),
+                        (None, false) => "NO".to_string(),
+                    }
                 )
             })
             .collect::<Vec<_>>()
-            .join("\n")
+            .join("\n");
+        write!(f, "{}", lines)
     }
 }
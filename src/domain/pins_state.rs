@@ -0,0 +1,64 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use teloxide::prelude::ChatId;
+use teloxide::types::MessageId;
+
+// the page announcement pinned by the bot in every chat, to be replaced by the next one
+#[derive(Debug, Default, Clone)]
+pub struct PinsState {
+    pinned: HashMap<i64, i32>,
+}
+
+impl PinsState {
+    pub fn by_chat(&self, chat_id: ChatId) -> Option<MessageId> {
+        self.pinned.get(&chat_id.0).map(|id| MessageId(*id))
+    }
+
+    pub fn set(&mut self, chat_id: ChatId, message_id: MessageId) {
+        self.pinned.insert(chat_id.0, message_id.0);
+    }
+
+    pub fn migrate(&mut self, from: ChatId, to: ChatId) -> bool {
+        match self.pinned.remove(&from.0) {
+            Some(message_id) => {
+                self.pinned.insert(to.0, message_id);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl FromStr for PinsState {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let pinned = s
+            .split('\n')
+            .filter(|l| !l.is_empty())
+            .map(|l| {
+                let l_split = l.split('\t').collect::<Vec<_>>();
+                (
+                    l_split[0].parse::<i64>().unwrap(),
+                    l_split[1].parse::<i32>().unwrap(),
+                )
+            })
+            .collect::<HashMap<_, _>>();
+
+        Ok(PinsState { pinned })
+    }
+}
+
+impl Display for PinsState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let lines = self
+            .pinned
+            .iter()
+            .map(|(uid, message_id)| format!("{}\t{}", uid, message_id))
+            .collect::<Vec<_>>()
+            .join("\n");
+        write!(f, "{}", lines)
+    }
+}
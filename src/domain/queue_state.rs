@@ -0,0 +1,82 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use teloxide::prelude::ChatId;
+
+// new pages waiting to be delivered to chats, e.g. until their quiet hours are over
+#[derive(Debug, Default, Clone)]
+pub struct QueueState {
+    queued: HashMap<i64, BTreeSet<usize>>,
+}
+
+impl QueueState {
+    pub fn add(&mut self, chat_id: ChatId, idxs: Vec<usize>) {
+        self.queued.entry(chat_id.0).or_default().extend(idxs);
+    }
+
+    pub fn take(&mut self, chat_id: ChatId) -> Vec<usize> {
+        self.queued
+            .remove(&chat_id.0)
+            .map(|idxs| idxs.into_iter().collect())
+            .unwrap_or_default()
+    }
+
+    pub fn chat_ids(&self) -> Vec<ChatId> {
+        self.queued.keys().map(|id| ChatId(*id)).collect()
+    }
+
+    pub fn migrate(&mut self, from: ChatId, to: ChatId) -> bool {
+        match self.queued.remove(&from.0) {
+            Some(queued) => {
+                self.queued.insert(to.0, queued);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl FromStr for QueueState {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let queued = s
+            .split('\n')
+            .filter(|l| !l.is_empty())
+            .map(|l| {
+                let l_split = l.split('\t').collect::<Vec<_>>();
+                (
+                    l_split[0].parse::<i64>().unwrap(),
+                    l_split[1]
+                        .split('|')
+                        .map(|idx| idx.parse::<usize>().unwrap())
+                        .collect::<BTreeSet<_>>(),
+                )
+            })
+            .collect::<HashMap<_, _>>();
+
+        Ok(QueueState { queued })
+    }
+}
+
+impl Display for QueueState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let lines = self
+            .queued
+            .iter()
+            .map(|(uid, idxs)| {
+                format!(
+                    "{}\t{}",
+                    uid,
+                    idxs.iter()
+                        .map(|idx| idx.to_string())
+                        .collect::<Vec<_>>()
+                        .join("|")
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        write!(f, "{}", lines)
+    }
+}
@@ -0,0 +1,120 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+use teloxide::prelude::ChatId;
+
+// up to that many pages a day, more is not a drip anymore
+pub const MAX_PER_DAY: usize = 10;
+
+// the archive sent to a chat from a chosen page, a few pages a day
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reread {
+    // the page to send next
+    pub next: usize,
+    pub per_day: usize,
+    // local hour pages are sent at
+    pub hour: u32,
+    // local date pages were sent last
+    pub last_sent: Option<NaiveDate>,
+    pub paused: bool,
+}
+
+impl Reread {
+    pub fn new(from: usize, per_day: usize, hour: u32) -> Reread {
+        Reread {
+            next: from,
+            per_day,
+            hour,
+            last_sent: None,
+            paused: false,
+        }
+    }
+
+    // once a day, at the hour or later if the bot was down at the time
+    pub fn is_due(&self, local_hour: u32, local_date: NaiveDate) -> bool {
+        !self.paused && local_hour >= self.hour && self.last_sent != Some(local_date)
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct RereadState {
+    rereads: HashMap<i64, Reread>,
+}
+
+impl RereadState {
+    pub fn by_chat(&self, chat_id: ChatId) -> Option<Reread> {
+        self.rereads.get(&chat_id.0).cloned()
+    }
+
+    pub fn set(&mut self, chat_id: ChatId, reread: Reread) {
+        self.rereads.insert(chat_id.0, reread);
+    }
+
+    pub fn chat_ids(&self) -> Vec<ChatId> {
+        self.rereads.keys().map(|id| ChatId(*id)).collect()
+    }
+
+    pub fn remove(&mut self, chat_id: ChatId) -> bool {
+        self.rereads.remove(&chat_id.0).is_some()
+    }
+
+    pub fn migrate(&mut self, from: ChatId, to: ChatId) -> bool {
+        match self.rereads.remove(&from.0) {
+            Some(reread) => {
+                self.rereads.insert(to.0, reread);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl FromStr for RereadState {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let rereads = s
+            .split('\n')
+            .filter(|l| !l.is_empty())
+            .map(|l| {
+                let l_split = l.split('\t').collect::<Vec<_>>();
+                let reread = Reread {
+                    next: l_split[1].parse().unwrap(),
+                    per_day: l_split[2].parse().unwrap(),
+                    hour: l_split[3].parse().unwrap(),
+                    last_sent: NaiveDate::parse_from_str(l_split[4], "%Y-%m-%d").ok(),
+                    paused: l_split[5] == "paused",
+                };
+                (l_split[0].parse::<i64>().unwrap(), reread)
+            })
+            .collect::<HashMap<_, _>>();
+
+        Ok(RereadState { rereads })
+    }
+}
+
+impl Display for RereadState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let lines = self
+            .rereads
+            .iter()
+            .map(|(uid, r)| {
+                format!(
+                    "{}\t{}\t{}\t{}\t{}\t{}",
+                    uid,
+                    r.next,
+                    r.per_day,
+                    r.hour,
+                    r.last_sent
+                        .map(|d| d.format("%Y-%m-%d").to_string())
+                        .unwrap_or("NO".to_string()),
+                    if r.paused { "paused" } else { "active" }
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        write!(f, "{}", lines)
+    }
+}
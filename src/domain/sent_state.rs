@@ -0,0 +1,215 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use chrono::{DateTime, Duration, Utc};
+use teloxide::prelude::ChatId;
+use teloxide::types::MessageId;
+
+use crate::domain::settings_state::Cleanup;
+
+// telegram doesn't let bots delete messages older than that, no reason to keep track of them
+static DELETABLE_HOURS: i64 = 48;
+
+#[derive(Debug, Clone)]
+pub struct SentPage {
+    pub idx: usize,
+    pub at: DateTime<Utc>,
+    pub message_ids: Vec<i32>,
+}
+
+// messages of pages sent to chats, oldest first, to be cleaned up later
+#[derive(Debug, Default, Clone)]
+pub struct SentState {
+    sent: HashMap<i64, Vec<SentPage>>,
+}
+
+impl SentState {
+    pub fn add(&mut self, chat_id: ChatId, page: SentPage) {
+        let pages = self.sent.entry(chat_id.0).or_default();
+        pages.retain(|p| page.at - p.at < Duration::hours(DELETABLE_HOURS));
+        pages.push(page);
+    }
+
+    // messages the cleanup policy says are due to be deleted, except for the pinned one
+    pub fn take_expired(
+        &mut self,
+        chat_id: ChatId,
+        cleanup: Cleanup,
+        pinned: Option<MessageId>,
+        now: DateTime<Utc>,
+    ) -> Vec<MessageId> {
+        let pages = match self.sent.get_mut(&chat_id.0) {
+            Some(pages) => pages,
+            None => return vec![],
+        };
+        let expired = match cleanup {
+            Cleanup::Off => 0,
+            Cleanup::KeepPages(n) => pages.len().saturating_sub(n),
+            Cleanup::AfterHours(h) => pages
+                .iter()
+                .take_while(|p| now - p.at >= Duration::hours(h))
+                .count(),
+        };
+        pages
+            .drain(..expired)
+            .flat_map(|p| p.message_ids)
+            .map(MessageId)
+            .filter(|id| Some(*id) != pinned)
+            .collect()
+    }
+
+    pub fn take_all(&mut self, chat_id: ChatId) -> Vec<MessageId> {
+        self.sent
+            .remove(&chat_id.0)
+            .unwrap_or_default()
+            .into_iter()
+            .flat_map(|p| p.message_ids)
+            .map(MessageId)
+            .collect()
+    }
+
+    pub fn chat_ids(&self) -> Vec<ChatId> {
+        self.sent.keys().map(|id| ChatId(*id)).collect()
+    }
+
+    pub fn migrate(&mut self, from: ChatId, to: ChatId) -> bool {
+        match self.sent.remove(&from.0) {
+            Some(pages) => {
+                self.sent.insert(to.0, pages);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl FromStr for SentState {
+    type Err = String;
+
+    // a line per sent page
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let sent = s.split('\n').filter(|l| !l.is_empty()).fold(
+            HashMap::<i64, Vec<SentPage>>::new(),
+            |mut acc, l| {
+                let l_split = l.split('\t').collect::<Vec<_>>();
+                let page = SentPage {
+                    idx: l_split[1].parse().unwrap(),
+                    at: DateTime::parse_from_rfc3339(l_split[2])
+                        .unwrap()
+                        .with_timezone(&Utc),
+                    message_ids: l_split[3]
+                        .split('|')
+                        .map(|id| id.parse::<i32>().unwrap())
+                        .collect(),
+                };
+                acc.entry(l_split[0].parse::<i64>().unwrap())
+                    .or_default()
+                    .push(page);
+                acc
+            },
+        );
+
+        Ok(SentState { sent })
+    }
+}
+
+impl Display for SentState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let lines = self
+            .sent
+            .iter()
+            .flat_map(|(uid, pages)| {
+                pages.iter().map(move |p| {
+                    format!(
+                        "{}\t{}\t{}\t{}",
+                        uid,
+                        p.idx,
+                        p.at.to_rfc3339(),
+                        p.message_ids
+                            .iter()
+                            .map(|id| id.to_string())
+                            .collect::<Vec<_>>()
+                            .join("|")
+                    )
+                })
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        write!(f, "{}", lines)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHAT: ChatId = ChatId(42);
+
+    fn at(hours_ago: i64, now: DateTime<Utc>) -> DateTime<Utc> {
+        now - Duration::hours(hours_ago)
+    }
+
+    // a page a message, message ids are page idxs
+    fn sent(hours_ago: &[i64], now: DateTime<Utc>) -> SentState {
+        let mut state = SentState::default();
+        for (idx, h) in hours_ago.iter().enumerate() {
+            state.add(
+                CHAT,
+                SentPage {
+                    idx,
+                    at: at(*h, now),
+                    message_ids: vec![idx as i32],
+                },
+            );
+        }
+        state
+    }
+
+    #[test]
+    fn keeps_last_pages() {
+        let now = Utc::now();
+        let mut state = sent(&[4, 3, 2, 1], now);
+        let expired = state.take_expired(CHAT, Cleanup::KeepPages(3), None, now);
+        assert_eq!(expired, vec![MessageId(0)]);
+        // what's left is kept until there's more
+        assert!(state
+            .take_expired(CHAT, Cleanup::KeepPages(3), None, now)
+            .is_empty());
+    }
+
+    #[test]
+    fn expires_pages_after_hours() {
+        let now = Utc::now();
+        let mut state = sent(&[30, 24, 23, 1], now);
+        let expired = state.take_expired(CHAT, Cleanup::AfterHours(24), None, now);
+        assert_eq!(expired, vec![MessageId(0), MessageId(1)]);
+        assert!(state
+            .take_expired(CHAT, Cleanup::AfterHours(24), None, now)
+            .is_empty());
+    }
+
+    #[test]
+    fn keeps_everything_when_off() {
+        let now = Utc::now();
+        let mut state = sent(&[30, 1], now);
+        assert!(state.take_expired(CHAT, Cleanup::Off, None, now).is_empty());
+        assert_eq!(state.take_all(CHAT).len(), 2);
+    }
+
+    #[test]
+    fn spares_pinned_message() {
+        let now = Utc::now();
+        let mut state = sent(&[4, 3, 2, 1], now);
+        let expired = state.take_expired(CHAT, Cleanup::KeepPages(1), Some(MessageId(1)), now);
+        assert_eq!(expired, vec![MessageId(0), MessageId(2)]);
+    }
+
+    #[test]
+    fn forgets_pages_too_old_to_delete() {
+        let now = Utc::now();
+        let mut state = sent(&[50, 49, 47, 1], now);
+        // only pages within 48 hours of the latest one are kept track of
+        assert_eq!(state.take_all(CHAT), vec![MessageId(2), MessageId(3)]);
+    }
+}
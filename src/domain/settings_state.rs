@@ -0,0 +1,371 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Timelike, Utc, Weekday};
+use teloxide::prelude::ChatId;
+
+use crate::domain::i18n::Lang;
+
+static TRANSLATE_TO: [&str; 6] = ["ru", "uk", "de", "es", "fr", "pt"];
+static QUIET_HOURS: [Option<(u32, u32)>; 4] = [None, Some((22, 7)), Some((23, 8)), Some((0, 9))];
+static CLEANUPS: [Cleanup; 6] = [
+    Cleanup::Off,
+    Cleanup::KeepPages(5),
+    Cleanup::KeepPages(20),
+    Cleanup::AfterHours(1),
+    Cleanup::AfterHours(6),
+    Cleanup::AfterHours(24),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DeliveryFormat {
+    // original png files, uncompressed
+    Document,
+    // compressed, but previewed right in the chat
+    Photo,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum QuietMode {
+    // new pages are sent right away, but without a sound
+    Silent,
+    // new pages wait for quiet hours to end
+    Queue,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Digest {
+    // every new page is sent as soon as it's found
+    Off,
+    Daily,
+    // on mondays
+    Weekly,
+}
+
+// what to do with pages sent to the chat, once they've been read
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Cleanup {
+    Off,
+    KeepPages(usize),
+    // telegram lets bots delete messages for 48 hours only
+    AfterHours(i64),
+}
+
+// utc offsets are -12..=14 hours
+const MIN_UTC_OFFSET: i32 = -12;
+const MAX_UTC_OFFSET: i32 = 14;
+
+// positions of the settings in the settings menu, `s-{n}` toggles the n-th one
+pub mod setting {
+    pub const FORMAT: usize = 0;
+    pub const TRANSLATE_TO: usize = 1;
+    pub const NOTIFY: usize = 2;
+    pub const QUIET_HOURS: usize = 3;
+    pub const READER_MODE: usize = 4;
+    pub const LANG: usize = 5;
+    pub const QUIET_MODE: usize = 6;
+    pub const UTC_OFFSET: usize = 7;
+    pub const DIGEST: usize = 8;
+    pub const DIGEST_HOUR: usize = 9;
+    pub const PRIVATE_REPLIES: usize = 10;
+    pub const PIN_LATEST: usize = 11;
+    pub const CLEANUP: usize = 12;
+    pub const COMPACT: usize = 13;
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChatSettings {
+    pub format: DeliveryFormat,
+    pub translate_to: String,
+    pub notify: bool,
+    // [from, to) local hours, may wrap over midnight
+    pub quiet_hours: Option<(u32, u32)>,
+    pub quiet_mode: QuietMode,
+    pub utc_offset: i32,
+    pub digest: Digest,
+    // local hour digests are delivered at
+    pub digest_hour: u32,
+    // images and navigation only, no titles and texts
+    pub reader_mode: bool,
+    pub lang: Lang,
+    // groups only: navigation goes to members' private chats
+    pub private_replies: bool,
+    // the newest page announcement stays pinned
+    pub pin_latest: bool,
+    pub cleanup: Cleanup,
+    // image, title and text in a single message, when the text is short enough for a caption
+    pub compact: bool,
+}
+
+impl Default for ChatSettings {
+    fn default() -> Self {
+        ChatSettings {
+            format: DeliveryFormat::Document,
+            translate_to: TRANSLATE_TO[0].to_string(),
+            notify: true,
+            quiet_hours: None,
+            quiet_mode: QuietMode::Silent,
+            utc_offset: 0,
+            digest: Digest::Off,
+            digest_hour: 9,
+            reader_mode: false,
+            lang: Lang::En,
+            private_replies: false,
+            pin_latest: false,
+            cleanup: Cleanup::Off,
+            compact: false,
+        }
+    }
+}
+
+impl ChatSettings {
+    // telegram gives language codes like `en` or `pt-br`. pages are in english,
+    // so translations are into user's language only if it's one of those on offer
+    pub fn with_language(language_code: Option<&str>) -> ChatSettings {
+        let default = ChatSettings::default();
+        ChatSettings {
+            translate_to: language_code
+                .and_then(|code| code.split('-').next())
+                .map(|lang| lang.to_lowercase())
+                .filter(|lang| TRANSLATE_TO.contains(&lang.as_str()))
+                .unwrap_or(default.translate_to),
+            lang: Lang::from_code(language_code),
+            ..default
+        }
+    }
+
+    // every setting has a fixed position in the settings menu, pressing it switches to the next value
+    pub fn toggle(&mut self, n: usize) {
+        match n {
+            setting::FORMAT => {
+                self.format = match self.format {
+                    DeliveryFormat::Document => DeliveryFormat::Photo,
+                    DeliveryFormat::Photo => DeliveryFormat::Document,
+                }
+            }
+            setting::TRANSLATE_TO => {
+                self.translate_to = next_of(&TRANSLATE_TO, &self.translate_to.as_str()).to_string()
+            }
+            setting::NOTIFY => self.notify = !self.notify,
+            setting::QUIET_HOURS => self.quiet_hours = next_of(&QUIET_HOURS, &self.quiet_hours),
+            setting::READER_MODE => self.reader_mode = !self.reader_mode,
+            setting::LANG => self.lang = next_of(&Lang::ALL, &self.lang),
+            setting::QUIET_MODE => {
+                self.quiet_mode = match self.quiet_mode {
+                    QuietMode::Silent => QuietMode::Queue,
+                    QuietMode::Queue => QuietMode::Silent,
+                }
+            }
+            setting::UTC_OFFSET => {
+                self.utc_offset = match self.utc_offset {
+                    MAX_UTC_OFFSET => MIN_UTC_OFFSET,
+                    offset => offset + 1,
+                }
+            }
+            setting::DIGEST => {
+                self.digest = match self.digest {
+                    Digest::Off => Digest::Daily,
+                    Digest::Daily => Digest::Weekly,
+                    Digest::Weekly => Digest::Off,
+                }
+            }
+            setting::DIGEST_HOUR => self.digest_hour = (self.digest_hour + 1) % 24,
+            setting::PRIVATE_REPLIES => self.private_replies = !self.private_replies,
+            setting::PIN_LATEST => self.pin_latest = !self.pin_latest,
+            setting::CLEANUP => self.cleanup = next_of(&CLEANUPS, &self.cleanup),
+            setting::COMPACT => self.compact = !self.compact,
+            _ => log::warn!("unexpected setting {}", n),
+        }
+    }
+
+    // false if the offset is out of -12..=14 range
+    pub fn set_utc_offset(&mut self, offset: i32) -> bool {
+        match (MIN_UTC_OFFSET..=MAX_UTC_OFFSET).contains(&offset) {
+            true => {
+                self.utc_offset = offset;
+                true
+            }
+            false => false,
+        }
+    }
+
+    pub fn local_hour(&self, now: DateTime<Utc>) -> u32 {
+        self.local_time(now).hour()
+    }
+
+    pub fn local_date(&self, now: DateTime<Utc>) -> NaiveDate {
+        self.local_time(now).date_naive()
+    }
+
+    // chrono's timezones are not worth it for a whole-hours offset
+    fn local_time(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        now + Duration::hours(self.utc_offset as i64)
+    }
+
+    pub fn is_digest_time(&self, now: DateTime<Utc>) -> bool {
+        let local = self.local_time(now);
+        match self.digest {
+            Digest::Off => false,
+            Digest::Daily => local.hour() == self.digest_hour,
+            Digest::Weekly => local.weekday() == Weekday::Mon && local.hour() == self.digest_hour,
+        }
+    }
+
+    pub fn is_quiet_at(&self, now: DateTime<Utc>) -> bool {
+        let hour = self.local_hour(now);
+        match self.quiet_hours {
+            None => false,
+            Some((from, to)) if from <= to => from <= hour && hour < to,
+            Some((from, to)) => hour >= from || hour < to,
+        }
+    }
+}
+
+fn next_of<T: PartialEq + Clone>(values: &[T], current: &T) -> T {
+    let pos = values.iter().position(|v| v == current).unwrap_or(0);
+    values[(pos + 1) % values.len()].clone()
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct SettingsState {
+    settings: HashMap<i64, ChatSettings>,
+}
+
+impl SettingsState {
+    pub fn by_chat(&self, chat_id: ChatId) -> ChatSettings {
+        self.settings.get(&chat_id.0).cloned().unwrap_or_default()
+    }
+
+    pub fn set(&mut self, chat_id: ChatId, settings: ChatSettings) {
+        self.settings.insert(chat_id.0, settings);
+    }
+
+    pub fn migrate(&mut self, from: ChatId, to: ChatId) -> bool {
+        match self.settings.remove(&from.0) {
+            Some(settings) => {
+                self.settings.insert(to.0, settings);
+                true
+            }
+            None => false,
+        }
+    }
+
+    // false if the chat has its settings already
+    pub fn init(&mut self, chat_id: ChatId, settings: ChatSettings) -> bool {
+        match self.settings.contains_key(&chat_id.0) {
+            true => false,
+            false => {
+                self.settings.insert(chat_id.0, settings);
+                true
+            }
+        }
+    }
+}
+
+impl FromStr for SettingsState {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let settings = s
+            .split('\n')
+            .filter(|l| !l.is_empty())
+            .map(|l| {
+                let l_split = l.split('\t').collect::<Vec<_>>();
+                let settings = ChatSettings {
+                    format: match l_split[1] {
+                        "photo" => DeliveryFormat::Photo,
+                        _ => DeliveryFormat::Document,
+                    },
+                    translate_to: l_split[2].to_string(),
+                    notify: l_split[3] == "on",
+                    quiet_hours: l_split[4]
+                        .split_once('-')
+                        .and_then(|(from, to)| from.parse().ok().zip(to.parse().ok())),
+                    reader_mode: l_split[5] == "on",
+                    lang: Lang::from_code(l_split.get(6).cloned()),
+                    quiet_mode: match l_split.get(7) {
+                        Some(&"queue") => QuietMode::Queue,
+                        _ => QuietMode::Silent,
+                    },
+                    utc_offset: l_split
+                        .get(8)
+                        .and_then(|o| o.parse::<i32>().ok())
+                        .unwrap_or(0),
+                    digest: match l_split.get(9) {
+                        Some(&"daily") => Digest::Daily,
+                        Some(&"weekly") => Digest::Weekly,
+                        _ => Digest::Off,
+                    },
+                    digest_hour: l_split
+                        .get(10)
+                        .and_then(|h| h.parse::<u32>().ok())
+                        .unwrap_or(9),
+                    private_replies: l_split.get(11) == Some(&"on"),
+                    pin_latest: l_split.get(12) == Some(&"on"),
+                    cleanup: l_split
+                        .get(13)
+                        .and_then(|c| c.split_once(':'))
+                        .and_then(|c| match c {
+                            ("keep", n) => n.parse().ok().map(Cleanup::KeepPages),
+                            ("hours", h) => h.parse().ok().map(Cleanup::AfterHours),
+                            _ => None,
+                        })
+                        .unwrap_or(Cleanup::Off),
+                    compact: l_split.get(14) == Some(&"on"),
+                };
+                (l_split[0].parse::<i64>().unwrap(), settings)
+            })
+            .collect::<HashMap<_, _>>();
+
+        Ok(SettingsState { settings })
+    }
+}
+
+impl Display for SettingsState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let on_off = |b: bool| if b { "on" } else { "off" };
+        let lines = self
+            .settings
+            .iter()
+            .map(|(uid, s)| {
+                format!(
+                    "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+                    uid,
+                    match s.format {
+                        DeliveryFormat::Document => "document",
+                        DeliveryFormat::Photo => "photo",
+                    },
+                    s.translate_to,
+                    on_off(s.notify),
+                    s.quiet_hours
+                        .map(|(from, to)| format!("{}-{}", from, to))
+                        .unwrap_or("NO".to_string()),
+                    on_off(s.reader_mode),
+                    s.lang.code(),
+                    match s.quiet_mode {
+                        QuietMode::Silent => "silent",
+                        QuietMode::Queue => "queue",
+                    },
+                    s.utc_offset,
+                    match s.digest {
+                        Digest::Off => "off",
+                        Digest::Daily => "daily",
+                        Digest::Weekly => "weekly",
+                    },
+                    s.digest_hour,
+                    on_off(s.private_replies),
+                    on_off(s.pin_latest),
+                    match s.cleanup {
+                        Cleanup::Off => "off".to_string(),
+                        Cleanup::KeepPages(n) => format!("keep:{}", n),
+                        Cleanup::AfterHours(h) => format!("hours:{}", h),
+                    },
+                    on_off(s.compact)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        write!(f, "{}", lines)
+    }
+}
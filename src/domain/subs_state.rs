@@ -1,23 +1,68 @@
 use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
 use std::str::FromStr;
-use std::string::ToString;
 use teloxide::prelude::ChatId;
 
 #[derive(Debug, Default, Clone)]
 pub struct SubsState {
     subscribers: HashMap<i64, usize>,
+    // forum topics subscribed instead of the whole chat
+    threads: HashMap<i64, i32>,
 }
 
 impl SubsState {
+    // re-subscribing keeps the reading position
     pub fn add(&mut self, uid: i64) {
-        self.subscribers.insert(uid, 0);
+        self.subscribers.entry(uid).or_insert(0);
+    }
+
+    // false if the topic is the same
+    pub fn set_thread(&mut self, uid: i64, thread_id: Option<i32>) -> bool {
+        match thread_id {
+            Some(thread_id) => self.threads.insert(uid, thread_id) != Some(thread_id),
+            None => self.threads.remove(&uid).is_some(),
+        }
+    }
+
+    pub fn thread(&self, uid: i64) -> Option<i32> {
+        self.threads.get(&uid).cloned()
+    }
+
+    pub fn last_idx(&self, uid: i64) -> Option<usize> {
+        self.subscribers.get(&uid).cloned()
+    }
+
+    // false if there is no such subscriber or position is the same
+    pub fn set_last_idx(&mut self, uid: i64, idx: usize) -> bool {
+        match self.subscribers.get_mut(&uid) {
+            Some(last_idx) if *last_idx != idx => {
+                *last_idx = idx;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn remove(&mut self, uid: i64) -> bool {
+        self.threads.remove(&uid);
+        self.subscribers.remove(&uid).is_some()
+    }
+
+    // a group turned into a supergroup keeps reading where it stopped, under its new id.
+    // plain groups have no topics, so there are none to carry over
+    pub fn migrate(&mut self, from: i64, to: i64) -> bool {
+        self.threads.remove(&from);
+        match self.subscribers.remove(&from) {
+            Some(last_idx) => {
+                self.subscribers.insert(to, last_idx);
+                true
+            }
+            None => false,
+        }
     }
 
     pub fn chat_ids(self) -> Vec<ChatId> {
-        self.subscribers
-            .keys()
-            .map(|id| ChatId(id.clone()))
-            .collect()
+        self.subscribers.keys().map(|id| ChatId(*id)).collect()
     }
 }
 
@@ -25,27 +70,48 @@ impl FromStr for SubsState {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let subscribers = s
+        let rows = s
             .split('\n')
-            .map(|l| {
-                let l_split = l.split('\t').collect::<Vec<_>>();
+            .filter(|l| !l.is_empty())
+            .map(|l| l.split('\t').collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let subscribers = rows
+            .iter()
+            .map(|l_split| {
                 (
                     l_split[0].parse::<i64>().unwrap(),
                     l_split[1].parse::<usize>().unwrap(),
                 )
             })
             .collect::<HashMap<_, _>>();
+        let threads = rows
+            .iter()
+            .filter_map(|l_split| {
+                l_split
+                    .get(2)
+                    .and_then(|t| t.parse::<i32>().ok())
+                    .map(|t| (l_split[0].parse::<i64>().unwrap(), t))
+            })
+            .collect::<HashMap<_, _>>();
 
-        Ok(SubsState { subscribers })
+        Ok(SubsState {
+            subscribers,
+            threads,
+        })
     }
 }
 
-impl ToString for SubsState {
-    fn to_string(&self) -> String {
-        self.subscribers
+impl Display for SubsState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let lines = self
+            .subscribers
             .iter()
-            .map(|(uid, last_idx)| format!("{}\t{}", uid, last_idx))
+            .map(|(uid, last_idx)| match self.threads.get(uid) {
+                Some(thread_id) => format!("{}\t{}\t{}", uid, last_idx, thread_id),
+                None => format!("{}\t{}", uid, last_idx),
+            })
             .collect::<Vec<_>>()
-            .join("\n")
+            .join("\n");
+        write!(f, "{}", lines)
     }
 }
@@ -0,0 +1,30 @@
+use std::fmt;
+
+#[derive(Debug)]
+pub enum TranslateError {
+    RequestErr(String, reqwest::Error),
+    BadResponse(String, String),
+}
+
+impl TranslateError {
+    pub fn req_err(url: &str, err: reqwest::Error) -> TranslateError {
+        TranslateError::RequestErr(url.to_string(), err)
+    }
+
+    pub fn bad_response(url: &str, body: &str) -> TranslateError {
+        TranslateError::BadResponse(url.to_string(), body.to_string())
+    }
+}
+
+impl fmt::Display for TranslateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TranslateError::RequestErr(url, err) => {
+                write!(f, "Failed to translate ({}): {}", url, err)
+            }
+            TranslateError::BadResponse(url, body) => {
+                write!(f, "Unexpected translation ({}): {}", url, body)
+            }
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use std::path;
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use lazy_static::lazy_static;
+use tokio::fs;
+
+use crate::cfg::DATA_PATH;
+use crate::domain::bookmarks_state::BookmarksState;
+
+lazy_static! {
+    static ref STATE_PATH: String = format!("{}/bookmarks_state.txt", DATA_PATH.as_str());
+}
+
+#[async_trait]
+pub trait BookmarksStateManager {
+    async fn load_bookmarks_state(&self) -> BookmarksState;
+    async fn save_bookmarks_state(&self, state: &BookmarksState);
+}
+
+// prod implementation
+#[derive(Clone, Copy)]
+pub struct BookmarksStateManagerImpl {}
+
+#[async_trait]
+impl BookmarksStateManager for BookmarksStateManagerImpl {
+    async fn load_bookmarks_state(&self) -> BookmarksState {
+        match path::Path::new(STATE_PATH.as_str()).exists() {
+            false => BookmarksState::default(),
+            true => fs::read_to_string(STATE_PATH.as_str())
+                .await
+                .map_err(|e| e.to_string())
+                .and_then(|s| BookmarksState::from_str(s.as_str()))
+                .unwrap(),
+        }
+    }
+
+    async fn save_bookmarks_state(&self, state: &BookmarksState) {
+        fs::write(STATE_PATH.as_str(), state.to_string())
+            .await
+            .unwrap()
+    }
+}
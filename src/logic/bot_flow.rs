@@ -1,53 +1,237 @@
 use std::sync::Arc;
 
+use chrono::Utc;
+use reqwest::Url;
 use teloxide::prelude::*;
-use teloxide::types::{BotCommand, InlineKeyboardButton, InlineKeyboardMarkup, MenuButton};
-use teloxide::utils::command::BotCommands;
-use teloxide::Bot;
+use teloxide::types::{
+    Chat, InlineKeyboardButton, InlineKeyboardButtonKind, InlineKeyboardMarkup, InlineQueryResult,
+    InlineQueryResultArticle, InlineQueryResultCachedDocument, InputMessageContent,
+    InputMessageContentText, MenuButton, MessageKind, ParseMode, User,
+};
+use teloxide::{Bot, RequestError};
 
-use crate::domain::bot_cmd::Command;
+use crate::cfg::{BOT_NAME, BURST_THRESHOLD};
+use crate::domain::bot_cmd::{bot_commands, help_text};
+use crate::domain::club_state::Club;
+use crate::domain::html_text;
+use crate::domain::html_text::MAX_TEXT_LEN;
+use crate::domain::i18n::{Lang, Msg};
+use crate::domain::ksbd_page::KsbdPage;
 use crate::domain::page_to_send::PageToSend;
+use crate::domain::reread_state::{Reread, MAX_PER_DAY};
+use crate::domain::sent_state::SentPage;
+use crate::domain::settings_state::{
+    setting, ChatSettings, Cleanup, DeliveryFormat, Digest, QuietMode,
+};
 use crate::logic::bot_state::BotStateManager;
 use crate::logic::page_sender::*;
-use crate::logic::HandlerResult;
+use crate::logic::translator::{GoogleLinkTranslator, Translation, Translator};
+use crate::logic::{HandlerResult, SentResult};
 
+// deep links look like t.me/ksbd_bot?start=p123, payload is everything after `start=`
 pub async fn start(
     state: Arc<dyn BotStateManager + Send + Sync>,
     bot: Bot,
     msg: Message,
+    payload: String,
 ) -> HandlerResult {
-    state.add_subs(msg.chat.id).await;
-
-    bot.set_my_commands(vec![
-        BotCommand::new("first", "first page"),
-        BotCommand::new("last", "last available page"),
-        BotCommand::new("jump", "jump to some page"),
-        BotCommand::new("help", "available commands"),
-    ])
-    .await?;
+    init_settings(&*state, &msg).await;
+    if !sent_by_admin(&bot, &msg).await? {
+        let lang = lang_of(&*state, msg.chat.id).await;
+        return no_page_in(bot, msg.chat.id, topic_of(&msg), Msg::AdminsOnly, lang).await;
+    }
+    // /start in a forum topic subscribes that very topic
+    let thread_id = topic_of(&msg);
+    state.add_subs(msg.chat.id, thread_id).await;
 
     log::info!(
-        "new user subscribed: [{}, {:?}]",
+        "new user subscribed: [{}, {:?}, topic {:?}]",
         msg.chat.id,
-        msg.chat.username()
+        msg.chat.username(),
+        thread_id
     );
 
-    bot.set_chat_menu_button()
-        .chat_id(msg.chat.id)
-        .menu_button(MenuButton::Commands)
-        .await?;
+    // commands are global and the menu button is for private chats only, no need to redo it from groups
+    if msg.chat.is_private() {
+        set_commands(&bot).await?;
+        bot.set_chat_menu_button()
+            .chat_id(msg.chat.id)
+            .menu_button(MenuButton::Commands)
+            .await?;
+    }
 
-    help(bot, msg).await
+    let chat_id = msg.chat.id;
+    help(state.clone(), bot.clone(), msg).await?;
+
+    match payload
+        .strip_prefix('p')
+        .and_then(|idx_str| idx_str.parse::<usize>().ok())
+    {
+        Some(idx) => by_idx_internal(state, bot, chat_id, thread_id, idx).await,
+        None => Ok(()),
+    }
+}
+
+// english commands for everybody, plus a translated set for every other language we speak
+async fn set_commands(bot: &Bot) -> HandlerResult {
+    for lang in Lang::ALL {
+        match lang {
+            Lang::En => bot.set_my_commands(bot_commands(lang)).await?,
+            _ => {
+                bot.set_my_commands(bot_commands(lang))
+                    .language_code(lang.code())
+                    .await?
+            }
+        };
+    }
+    Ok(())
 }
 
-pub async fn help(bot: Bot, msg: Message) -> HandlerResult {
-    bot.send_message(msg.chat.id, Command::descriptions().to_string())
-        .await?;
+pub async fn help(
+    state: Arc<dyn BotStateManager + Send + Sync>,
+    bot: Bot,
+    msg: Message,
+) -> HandlerResult {
+    let lang = lang_of(&*state, msg.chat.id).await;
+    let mut req = bot.send_message(msg.chat.id, help_text(lang));
+    req.message_thread_id = topic_of(&msg);
+    req.await?;
     Ok(())
 }
 
-async fn no_page(bot: Bot, id: ChatId, no_str: &str) -> HandlerResult {
-    bot.send_message(id, no_str).await?;
+async fn lang_of(state: &(dyn BotStateManager + Send + Sync), chat_id: ChatId) -> Lang {
+    state.settings(chat_id).await.lang
+}
+
+// in groups replying in private, pages go to the member who asked, given they've started the bot.
+// otherwise to the chat itself, in the forum topic the request came from
+async fn reader_of(
+    state: &(dyn BotStateManager + Send + Sync),
+    msg: &Message,
+    user: Option<&User>,
+) -> (ChatId, Option<i32>) {
+    let chat = &msg.chat;
+    match user {
+        Some(u) if !chat.is_private() && state.settings(chat.id).await.private_replies => {
+            let private_chat = ChatId::from(u.id);
+            match state.subs_chat_ids().await.contains(&private_chat) {
+                true => (private_chat, None),
+                false => (chat.id, topic_of(msg)),
+            }
+        }
+        _ => (chat.id, topic_of(msg)),
+    }
+}
+
+// replies in groups have threads too, only forum topics count
+fn topic_of(msg: &Message) -> Option<i32> {
+    match &msg.kind {
+        MessageKind::Common(common) if common.is_topic_message => msg.thread_id,
+        _ => None,
+    }
+}
+
+// sends the page and remembers file ids of uploaded images, so they could be reused later.
+// the page becomes the chat's current one
+pub async fn send_page(
+    state: &(impl BotStateManager + ?Sized),
+    sender: &impl PageSender,
+    mut p: PageToSend,
+    to: ChatId,
+) -> SentResult {
+    let idx = p.idx;
+    p.has_notes = !state.notes(to, idx).await.is_empty();
+    let settings = state.settings(to).await;
+    let sent = sender.send_full_page(p, to, &settings).await?;
+    state.set_position(to, idx).await;
+    state
+        .record_sent(
+            to,
+            SentPage {
+                idx,
+                at: Utc::now(),
+                message_ids: sent.iter().map(|m| m.id.0).collect(),
+            },
+        )
+        .await;
+
+    if state.file_ids(idx).await.is_none() {
+        let ids = sent
+            .iter()
+            .filter_map(|m| m.document())
+            .map(|d| d.file.id.clone())
+            .collect::<Vec<_>>();
+        if !ids.is_empty() {
+            state.add_file_ids(idx, ids).await;
+        }
+    }
+
+    Ok(sent)
+}
+
+// fresh pages one by one, unless there are too many of them at once
+pub async fn send_new_pages(
+    state: &(impl BotStateManager + ?Sized),
+    sender: &impl PageSender,
+    mut pages: Vec<PageToSend>,
+    to: ChatId,
+) {
+    let thread_id = state.thread_of(to).await;
+    pages.iter_mut().for_each(|p| p.thread_id = thread_id);
+    let settings = state.settings(to).await;
+
+    // the first message of the latest page, or the burst announcement
+    let announcement = match pages.first() {
+        Some(first) if pages.len() > *BURST_THRESHOLD => sender
+            .send_burst(first, pages.len(), to, &settings)
+            .await
+            .ok()
+            .and_then(|sent| sent.first().map(|m| m.id)),
+        _ => {
+            let mut latest = None;
+            for p in pages {
+                if let Ok(sent) = send_page(state, sender, p, to).await {
+                    latest = sent.first().map(|m| m.id).or(latest);
+                }
+            }
+            latest
+        }
+    };
+
+    if let Some(message_id) = announcement.filter(|_| settings.pin_latest) {
+        match sender.pin(to, message_id, state.pinned(to).await).await {
+            Ok(_) => state.set_pinned(to, message_id).await,
+            Err(e) => log::error!("error pinning {} in {}: {}", message_id, to, e),
+        }
+    }
+}
+
+// a page opened on chat's request, goes to the chat's history
+async fn open_page(
+    state: &(dyn BotStateManager + Send + Sync),
+    bot: &Bot,
+    p: KsbdPage,
+    to: ChatId,
+    thread_id: Option<i32>,
+) -> SentResult {
+    state.record_history(to, p.idx).await;
+    let p = PageToSend {
+        thread_id,
+        ..PageToSend::old_page(p)
+    };
+    send_page(state, bot, p, to).await
+}
+
+async fn no_page_in(
+    bot: Bot,
+    id: ChatId,
+    thread_id: Option<i32>,
+    no_msg: Msg,
+    lang: Lang,
+) -> HandlerResult {
+    let mut req = bot.send_message(id, no_msg.text(lang));
+    req.message_thread_id = thread_id;
+    req.await?;
     Ok(())
 }
 
@@ -56,11 +240,12 @@ pub async fn first(
     bot: Bot,
     msg: Message,
 ) -> HandlerResult {
+    let lang = lang_of(&*state, msg.chat.id).await;
+    let (to, thread_id) = reader_of(&*state, &msg, msg.from()).await;
     match state.first().await {
-        None => no_page(bot, msg.chat.id, ":( no first page").await?,
+        None => no_page_in(bot, to, thread_id, Msg::NoFirstPage, lang).await?,
         Some(p) => {
-            bot.send_full_page(PageToSend::old_page(p), msg.chat.id)
-                .await?
+            open_page(&*state, &bot, p, to, thread_id).await?;
         }
     };
     Ok(())
@@ -71,31 +256,74 @@ pub async fn last(
     bot: Bot,
     msg: Message,
 ) -> HandlerResult {
+    let lang = lang_of(&*state, msg.chat.id).await;
+    let (to, thread_id) = reader_of(&*state, &msg, msg.from()).await;
     match state.last().await {
-        None => no_page(bot, msg.chat.id, ":( no last page").await?,
+        None => no_page_in(bot, to, thread_id, Msg::NoLastPage, lang).await?,
         Some(p) => {
-            bot.send_full_page(PageToSend::old_page(p), msg.chat.id)
-                .await?
+            open_page(&*state, &bot, p, to, thread_id).await?;
         }
     };
     Ok(())
 }
 
+pub async fn random(
+    state: Arc<dyn BotStateManager + Send + Sync>,
+    bot: Bot,
+    msg: Message,
+) -> HandlerResult {
+    let lang = lang_of(&*state, msg.chat.id).await;
+    let (to, thread_id) = reader_of(&*state, &msg, msg.from()).await;
+    match state.random().await {
+        None => no_page_in(bot, to, thread_id, Msg::NoPages, lang).await?,
+        Some(p) => {
+            open_page(&*state, &bot, p, to, thread_id).await?;
+        }
+    };
+    Ok(())
+}
+
+pub async fn on_this_day(
+    state: Arc<dyn BotStateManager + Send + Sync>,
+    bot: Bot,
+    msg: Message,
+) -> HandlerResult {
+    let lang = lang_of(&*state, msg.chat.id).await;
+    let (to, thread_id) = reader_of(&*state, &msg, msg.from()).await;
+    let pages = state.on_this_day(Utc::now()).await;
+    if pages.is_empty() {
+        no_page_in(bot.clone(), to, thread_id, Msg::NothingOnThisDay, lang).await?;
+    }
+    for p in pages {
+        let p = PageToSend {
+            thread_id,
+            ..PageToSend::old_page(p)
+        };
+        send_page(&*state, &bot, p, to).await?;
+    }
+    Ok(())
+}
+
 async fn by_idx_internal(
     state: Arc<dyn BotStateManager + Send + Sync>,
     bot: Bot,
     id: ChatId,
+    thread_id: Option<i32>,
     idx: usize,
 ) -> HandlerResult {
+    let lang = lang_of(&*state, id).await;
     match state.by_idx(idx).await {
-        None => no_page(bot, id, format!(":( no page at idx {}", idx).as_str()).await?,
-        Some(p) => bot.send_full_page(PageToSend::old_page(p), id).await?,
+        None => no_page_in(bot, id, thread_id, Msg::NoPageAt(idx), lang).await?,
+        Some(p) => {
+            open_page(&*state, &bot, p, id, thread_id).await?;
+        }
     };
     Ok(())
 }
 
 pub async fn nav_callback(
     state: Arc<dyn BotStateManager + Send + Sync>,
+    translator: Arc<dyn Translator + Send + Sync>,
     bot: Bot,
     q: CallbackQuery,
 ) -> HandlerResult {
@@ -104,10 +332,67 @@ pub async fn nav_callback(
             .split_once('-')
             .and_then(|(cmd, idx_str)| idx_str.parse::<usize>().ok().map(|idx| (cmd, idx)));
 
-        let chat_id = q.message.unwrap().chat.id;
+        let message = q.message.clone().unwrap();
+        let chat_id = message.chat.id;
+        let lang = lang_of(&*state, chat_id).await;
+        let (reader, thread_id) = reader_of(&*state, &message, Some(&q.from)).await;
 
         match maybe_cmd_and_idx {
-            Some(("n", idx)) => by_idx_internal(state, bot, chat_id, idx).await?,
+            Some(("n", idx)) => by_idx_internal(state, bot, reader, thread_id, idx).await?,
+            Some(("b", idx)) => {
+                let answer = match state.add_bookmark(chat_id, idx).await {
+                    true => Msg::Bookmarked(idx).text(lang),
+                    false => Msg::BookmarkedAlready(idx).text(lang),
+                };
+                bot.answer_callback_query(q.id.clone()).text(answer).await?;
+            }
+            Some(("k", idx)) => {
+                state.history_back_to(reader, idx).await;
+                by_idx_internal(state, bot, reader, thread_id, idx).await?
+            }
+            Some(("s", _)) if !is_admin(&bot, &message.chat, Some(&q.from)).await? => {
+                bot.answer_callback_query(q.id.clone())
+                    .text(Msg::AdminsOnly.text(lang))
+                    .await?;
+            }
+            Some(("s", setting)) => {
+                let mut settings = state.settings(chat_id).await;
+                settings.toggle(setting);
+                state.set_settings(chat_id, settings.clone()).await;
+                bot.edit_message_reply_markup(chat_id, message.id)
+                    .reply_markup(settings_kb(&settings, &message.chat))
+                    .await?;
+            }
+            Some(("x", _)) => {
+                let settings = state.settings(chat_id).await;
+                translate(&*translator, &bot, &message, &settings).await?
+            }
+            Some(("t", idx)) => {
+                let notes = state.notes(chat_id, idx).await;
+                send_notes(bot, chat_id, topic_of(&message), vec![(idx, notes)], lang).await?
+            }
+            Some(("v", next)) => {
+                let answer = match state.vote_club(chat_id, q.from.id.0, next).await {
+                    Some(club) => {
+                        // a repeated vote leaves the button as is, and telegram refuses such edits
+                        let _ = bot
+                            .edit_message_reply_markup(chat_id, message.id)
+                            .reply_markup(club_kb(&club, lang))
+                            .await;
+                        Msg::VoteCounted
+                    }
+                    None => Msg::VoteStale,
+                };
+                bot.answer_callback_query(q.id.clone())
+                    .text(answer.text(lang))
+                    .await?;
+            }
+            Some(("r", idx)) => {
+                state.remove_bookmark(chat_id, idx).await;
+                bot.edit_message_reply_markup(chat_id, message.id)
+                    .reply_markup(bookmarks_kb(&*state, chat_id, lang).await)
+                    .await?;
+            }
             _ => log::warn!("unexpected callback {}", cmd),
         }
     }
@@ -115,6 +400,576 @@ pub async fn nav_callback(
     Ok(())
 }
 
+// replies to the message with its translation, or at least with a link to it
+async fn translate(
+    translator: &(dyn Translator + Send + Sync),
+    bot: &Bot,
+    message: &Message,
+    settings: &ChatSettings,
+) -> HandlerResult {
+    // compact pages have their text in the caption
+    let txt = message.text().or(message.caption()).unwrap_or_default();
+    let translation = translator
+        .translate(txt, &settings.translate_to)
+        .await
+        .unwrap_or_else(|e| {
+            log::error!("error translating: {}", e);
+            Translation::Link(GoogleLinkTranslator::link(txt, &settings.translate_to))
+        });
+
+    match translation {
+        // translations tend to be longer than the originals
+        Translation::Text(translated) => {
+            for txt in html_text::split(&[html_text::escape(&translated)], MAX_TEXT_LEN) {
+                bot.send_message(message.chat.id, txt)
+                    .parse_mode(ParseMode::Html)
+                    .reply_to_message_id(message.id)
+                    .await?;
+            }
+        }
+        Translation::Link(url) => {
+            bot.send_message(message.chat.id, Msg::Translation.text(settings.lang))
+                .reply_to_message_id(message.id)
+                .reply_markup(InlineKeyboardMarkup::new(vec![vec![
+                    InlineKeyboardButton::url(Msg::Open.text(settings.lang), url),
+                ]]))
+                .await?;
+        }
+    };
+    Ok(())
+}
+
+pub async fn bookmarks(
+    state: Arc<dyn BotStateManager + Send + Sync>,
+    bot: Bot,
+    msg: Message,
+) -> HandlerResult {
+    let lang = lang_of(&*state, msg.chat.id).await;
+    let thread_id = topic_of(&msg);
+    match state.bookmarks(msg.chat.id).await.is_empty() {
+        true => no_page_in(bot, msg.chat.id, thread_id, Msg::NoBookmarks, lang).await?,
+        false => {
+            let mut req = bot
+                .send_message(msg.chat.id, Msg::Bookmarks.text(lang))
+                .reply_markup(bookmarks_kb(&*state, msg.chat.id, lang).await);
+            req.message_thread_id = thread_id;
+            req.await?;
+        }
+    };
+    Ok(())
+}
+
+// a row per bookmarked page: open it or remove it from bookmarks
+async fn bookmarks_kb(
+    state: &(dyn BotStateManager + Send + Sync),
+    chat_id: ChatId,
+    lang: Lang,
+) -> InlineKeyboardMarkup {
+    let mut btn_rows = vec![];
+    for idx in state.bookmarks(chat_id).await {
+        btn_rows.push(vec![
+            InlineKeyboardButton::callback(btn_title(state, idx).await, format!("n-{}", idx)),
+            InlineKeyboardButton::callback(Msg::Remove.text(lang), format!("r-{}", idx)),
+        ]);
+    }
+
+    InlineKeyboardMarkup::new(btn_rows)
+}
+
+pub async fn note(
+    state: Arc<dyn BotStateManager + Send + Sync>,
+    bot: Bot,
+    msg: Message,
+    text: String,
+) -> HandlerResult {
+    // replying with /note to a page message attaches the note to that page
+    let maybe_idx = match msg.reply_to_message().and_then(page_idx_of) {
+        Some(idx) => Some(idx),
+        None => state.position(msg.chat.id).await,
+    };
+    add_note_internal(state, bot, msg.chat.id, topic_of(&msg), maybe_idx, text).await
+}
+
+pub async fn note_reply(
+    state: Arc<dyn BotStateManager + Send + Sync>,
+    bot: Bot,
+    msg: Message,
+) -> HandlerResult {
+    let maybe_idx = msg.reply_to_message().and_then(page_idx_of);
+    let text = msg.text().unwrap_or_default().to_string();
+    match maybe_idx {
+        Some(idx) => {
+            add_note_internal(state, bot, msg.chat.id, topic_of(&msg), Some(idx), text).await
+        }
+        None => Ok(()),
+    }
+}
+
+async fn add_note_internal(
+    state: Arc<dyn BotStateManager + Send + Sync>,
+    bot: Bot,
+    id: ChatId,
+    thread_id: Option<i32>,
+    maybe_idx: Option<usize>,
+    text: String,
+) -> HandlerResult {
+    let lang = lang_of(&*state, id).await;
+    let text = text.trim().to_string();
+    match (maybe_idx, text.is_empty()) {
+        (_, true) => no_page_in(bot, id, thread_id, Msg::NoteUsage, lang).await?,
+        (None, _) => no_page_in(bot, id, thread_id, Msg::OpenPageFirst, lang).await?,
+        (Some(idx), false) => {
+            state.add_note(id, idx, text).await;
+            no_page_in(bot, id, thread_id, Msg::NoteAdded(idx), lang).await?;
+        }
+    };
+    Ok(())
+}
+
+// the page a message belongs to, taken from its navigation buttons
+pub fn page_idx_of(msg: &Message) -> Option<usize> {
+    msg.reply_markup()?
+        .inline_keyboard
+        .iter()
+        .flatten()
+        .find_map(|btn| match &btn.kind {
+            InlineKeyboardButtonKind::CallbackData(data) => data
+                .strip_prefix("b-")
+                .and_then(|idx_str| idx_str.parse::<usize>().ok()),
+            _ => None,
+        })
+}
+
+pub async fn notes(
+    state: Arc<dyn BotStateManager + Send + Sync>,
+    bot: Bot,
+    msg: Message,
+) -> HandlerResult {
+    let lang = lang_of(&*state, msg.chat.id).await;
+    let thread_id = topic_of(&msg);
+    let all_notes = state.all_notes(msg.chat.id).await;
+    match all_notes.is_empty() {
+        true => no_page_in(bot, msg.chat.id, thread_id, Msg::NoNotes, lang).await,
+        false => send_notes(bot, msg.chat.id, thread_id, all_notes, lang).await,
+    }
+}
+
+async fn send_notes(
+    bot: Bot,
+    id: ChatId,
+    thread_id: Option<i32>,
+    notes: Vec<(usize, Vec<String>)>,
+    lang: Lang,
+) -> HandlerResult {
+    let blocks = notes
+        .into_iter()
+        .map(|(idx, page_notes)| {
+            let lines = page_notes
+                .iter()
+                .map(|n| format!("📝 {}", html_text::escape(n)))
+                .collect::<Vec<_>>()
+                .join("\n");
+            format!("{}\n{}", Msg::PageNotes(idx).text(lang), lines)
+        })
+        .collect::<Vec<_>>();
+
+    for txt in html_text::split(&blocks, MAX_TEXT_LEN) {
+        let mut req = bot.send_message(id, txt).parse_mode(ParseMode::Html);
+        req.message_thread_id = thread_id;
+        req.await?;
+    }
+    Ok(())
+}
+
+pub async fn history(
+    state: Arc<dyn BotStateManager + Send + Sync>,
+    bot: Bot,
+    msg: Message,
+) -> HandlerResult {
+    let lang = lang_of(&*state, msg.chat.id).await;
+    let thread_id = topic_of(&msg);
+    let history = state.history(msg.chat.id).await;
+    if history.is_empty() {
+        return no_page_in(bot, msg.chat.id, thread_id, Msg::NoHistory, lang).await;
+    }
+
+    let mut btn_rows = vec![];
+    // the latest is the current page, the one before it is where the last jump was made from
+    if let Some(prev_idx) = history.iter().rev().nth(1) {
+        btn_rows.push(vec![InlineKeyboardButton::callback(
+            Msg::BackTo(*prev_idx).text(lang),
+            format!("k-{}", prev_idx),
+        )]);
+    }
+    for idx in history.iter().rev() {
+        btn_rows.push(vec![InlineKeyboardButton::callback(
+            btn_title(&*state, *idx).await,
+            format!("n-{}", idx),
+        )]);
+    }
+
+    let mut req = bot
+        .send_message(msg.chat.id, Msg::RecentPages.text(lang))
+        .reply_markup(InlineKeyboardMarkup::new(btn_rows));
+    req.message_thread_id = thread_id;
+    req.await?;
+    Ok(())
+}
+
+// page idx with a title short enough to fit a button
+async fn btn_title(state: &(dyn BotStateManager + Send + Sync), idx: usize) -> String {
+    static MAX_TITLE_LEN: usize = 40;
+
+    let title = state
+        .by_idx(idx)
+        .await
+        .and_then(|p| PageToSend::old_page(p).title)
+        .unwrap_or_default()
+        .chars()
+        .take(MAX_TITLE_LEN)
+        .collect::<String>();
+
+    format!("#{} {}", idx, title)
+}
+
+// chat's settings start with a language of the user who came first
+async fn init_settings(state: &(dyn BotStateManager + Send + Sync), msg: &Message) {
+    let language_code = msg.from().and_then(|u| u.language_code.as_deref());
+    state
+        .init_settings(msg.chat.id, ChatSettings::with_language(language_code))
+        .await
+}
+
+pub async fn settings(
+    state: Arc<dyn BotStateManager + Send + Sync>,
+    bot: Bot,
+    msg: Message,
+) -> HandlerResult {
+    init_settings(&*state, &msg).await;
+    let settings = state.settings(msg.chat.id).await;
+    let mut req = bot
+        .send_message(msg.chat.id, Msg::Settings.text(settings.lang))
+        .reply_markup(settings_kb(&settings, &msg.chat));
+    req.message_thread_id = topic_of(&msg);
+    req.await?;
+    Ok(())
+}
+
+// a button per setting, `s-{n}` switches the n-th setting to its next value
+fn settings_kb(settings: &ChatSettings, chat: &Chat) -> InlineKeyboardMarkup {
+    let lang = settings.lang;
+    let on_off = |b: bool| if b { Msg::On } else { Msg::Off }.text(lang);
+    let labels = vec![
+        (
+            setting::FORMAT,
+            Msg::SetImages,
+            match settings.format {
+                DeliveryFormat::Document => Msg::Files,
+                DeliveryFormat::Photo => Msg::Photos,
+            }
+            .text(lang),
+        ),
+        (
+            setting::TRANSLATE_TO,
+            Msg::SetTranslateTo,
+            settings.translate_to.clone(),
+        ),
+        (setting::NOTIFY, Msg::SetNewPages, on_off(settings.notify)),
+        (
+            setting::QUIET_HOURS,
+            Msg::SetQuietHours,
+            settings
+                .quiet_hours
+                .map(|(from, to)| format!("{}:00-{}:00", from, to))
+                .unwrap_or(on_off(false)),
+        ),
+        (
+            setting::READER_MODE,
+            Msg::SetReaderMode,
+            on_off(settings.reader_mode),
+        ),
+        (setting::LANG, Msg::SetLanguage, lang.code().to_string()),
+        (
+            setting::QUIET_MODE,
+            Msg::SetQuietMode,
+            match settings.quiet_mode {
+                QuietMode::Silent => Msg::Silent,
+                QuietMode::Queue => Msg::Queue,
+            }
+            .text(lang),
+        ),
+        (
+            setting::UTC_OFFSET,
+            Msg::SetTimezone,
+            format!("UTC{:+}", settings.utc_offset),
+        ),
+        (
+            setting::DIGEST,
+            Msg::SetDigest,
+            match settings.digest {
+                Digest::Off => on_off(false),
+                Digest::Daily => Msg::Daily.text(lang),
+                Digest::Weekly => Msg::Weekly.text(lang),
+            },
+        ),
+        (
+            setting::DIGEST_HOUR,
+            Msg::SetDigestHour,
+            format!("{}:00", settings.digest_hour),
+        ),
+        (
+            setting::PRIVATE_REPLIES,
+            Msg::SetReplies,
+            match settings.private_replies {
+                true => Msg::InPrivate,
+                false => Msg::InChat,
+            }
+            .text(lang),
+        ),
+        (
+            setting::PIN_LATEST,
+            Msg::SetPinLatest,
+            on_off(settings.pin_latest),
+        ),
+        (
+            setting::CLEANUP,
+            Msg::SetCleanup,
+            match settings.cleanup {
+                Cleanup::Off => on_off(false),
+                Cleanup::KeepPages(n) => Msg::KeepPages(n).text(lang),
+                Cleanup::AfterHours(h) => Msg::AfterHours(h).text(lang),
+            },
+        ),
+        (setting::COMPACT, Msg::SetCompact, on_off(settings.compact)),
+    ];
+
+    // where to reply only matters for groups
+    InlineKeyboardMarkup::new(
+        labels
+            .into_iter()
+            .filter(|(n, _, _)| *n != setting::PRIVATE_REPLIES || !chat.is_private())
+            .map(|(n, name, value)| {
+                vec![InlineKeyboardButton::callback(
+                    format!("{}: {}", name.text(lang), value),
+                    format!("s-{}", n),
+                )]
+            }),
+    )
+}
+
+pub async fn timezone(
+    state: Arc<dyn BotStateManager + Send + Sync>,
+    bot: Bot,
+    msg: Message,
+    offset: String,
+) -> HandlerResult {
+    init_settings(&*state, &msg).await;
+    let mut settings = state.settings(msg.chat.id).await;
+    let lang = settings.lang;
+    let thread_id = topic_of(&msg);
+
+    if !sent_by_admin(&bot, &msg).await? {
+        return no_page_in(bot, msg.chat.id, thread_id, Msg::AdminsOnly, lang).await;
+    }
+
+    match offset.trim().trim_start_matches('+').parse::<i32>() {
+        Ok(offset) if settings.set_utc_offset(offset) => {
+            state.set_settings(msg.chat.id, settings).await;
+            let mut req = bot.send_message(msg.chat.id, Msg::TimezoneSet(offset).text(lang));
+            req.message_thread_id = thread_id;
+            req.await?;
+        }
+        _ => no_page_in(bot, msg.chat.id, thread_id, Msg::TimezoneUsage, lang).await?,
+    };
+    Ok(())
+}
+
+// `/reread` resumes a paused reread or starts from the first page, `/reread 100 2` starts from #100, 2 pages a day
+pub async fn reread(
+    state: Arc<dyn BotStateManager + Send + Sync>,
+    bot: Bot,
+    msg: Message,
+    args: String,
+) -> HandlerResult {
+    init_settings(&*state, &msg).await;
+    let settings = state.settings(msg.chat.id).await;
+    let lang = settings.lang;
+    let thread_id = topic_of(&msg);
+    let hour = settings.local_hour(Utc::now());
+
+    if !sent_by_admin(&bot, &msg).await? {
+        return no_page_in(bot, msg.chat.id, thread_id, Msg::AdminsOnly, lang).await;
+    }
+
+    let args = args
+        .split_whitespace()
+        .map(|a| a.parse::<usize>().ok())
+        .collect::<Option<Vec<_>>>();
+    let reread = match (args.as_deref(), state.reread(msg.chat.id).await) {
+        (Some([]), Some(r)) => Some(Reread { paused: false, ..r }),
+        (Some([]), None) => state.first().await.map(|p| Reread::new(p.idx, 1, hour)),
+        (Some([from]), _) => Some(Reread::new(*from, 1, hour)),
+        (Some([from, per_day]), _) if (1..=MAX_PER_DAY).contains(per_day) => {
+            Some(Reread::new(*from, *per_day, hour))
+        }
+        _ => None,
+    };
+
+    match reread {
+        None => no_page_in(bot, msg.chat.id, thread_id, Msg::RereadUsage, lang).await?,
+        Some(r) if state.by_idx(r.next).await.is_none() && r.last_sent.is_none() => {
+            no_page_in(bot, msg.chat.id, thread_id, Msg::NoPageAt(r.next), lang).await?
+        }
+        Some(r) => {
+            let mut req = bot.send_message(
+                msg.chat.id,
+                Msg::RereadFrom(r.next, r.per_day, r.hour).text(lang),
+            );
+            req.message_thread_id = thread_id;
+            req.await?;
+            state.set_reread(msg.chat.id, r).await;
+        }
+    };
+    Ok(())
+}
+
+pub async fn pause(
+    state: Arc<dyn BotStateManager + Send + Sync>,
+    bot: Bot,
+    msg: Message,
+) -> HandlerResult {
+    let lang = lang_of(&*state, msg.chat.id).await;
+    let thread_id = topic_of(&msg);
+
+    if !sent_by_admin(&bot, &msg).await? {
+        return no_page_in(bot, msg.chat.id, thread_id, Msg::AdminsOnly, lang).await;
+    }
+
+    match state.reread(msg.chat.id).await {
+        Some(r) if !r.paused => {
+            let mut req = bot.send_message(msg.chat.id, Msg::RereadPaused(r.next).text(lang));
+            req.message_thread_id = thread_id;
+            req.await?;
+            state
+                .set_reread(msg.chat.id, Reread { paused: true, ..r })
+                .await;
+        }
+        _ => no_page_in(bot, msg.chat.id, thread_id, Msg::NothingToPause, lang).await?,
+    };
+    Ok(())
+}
+
+// `/club 100 3` starts a book club from #100 with 3 pages a round, `/club off` ends it
+pub async fn club(
+    state: Arc<dyn BotStateManager + Send + Sync>,
+    bot: Bot,
+    msg: Message,
+    args: String,
+) -> HandlerResult {
+    init_settings(&*state, &msg).await;
+    let settings = state.settings(msg.chat.id).await;
+    let lang = settings.lang;
+    let thread_id = topic_of(&msg);
+
+    if msg.chat.is_private() || msg.chat.is_channel() {
+        return no_page_in(bot, msg.chat.id, thread_id, Msg::GroupsOnly, lang).await;
+    }
+    if !sent_by_admin(&bot, &msg).await? {
+        return no_page_in(bot, msg.chat.id, thread_id, Msg::AdminsOnly, lang).await;
+    }
+
+    if args.trim() == "off" {
+        let stopped = match state.remove_club(msg.chat.id).await {
+            true => Msg::ClubStopped,
+            false => Msg::NoClub,
+        };
+        return no_page_in(bot, msg.chat.id, thread_id, stopped, lang).await;
+    }
+
+    let args = args
+        .split_whitespace()
+        .map(|a| a.parse::<usize>().ok())
+        .collect::<Option<Vec<_>>>();
+    let from_and_per_round = match args.as_deref() {
+        Some([]) => state.first().await.map(|p| (p.idx, 1)),
+        Some([from]) => Some((*from, 1)),
+        Some([from, per_round]) if (1..=MAX_PER_DAY).contains(per_round) => {
+            Some((*from, *per_round))
+        }
+        _ => None,
+    };
+
+    match from_and_per_round {
+        None => no_page_in(bot, msg.chat.id, thread_id, Msg::ClubUsage, lang).await?,
+        Some((from, _)) if state.by_idx(from).await.is_none() => {
+            no_page_in(bot, msg.chat.id, thread_id, Msg::NoPageAt(from), lang).await?
+        }
+        Some((from, per_round)) => {
+            // half of the members, not counting the bot itself
+            let members = bot.get_chat_member_count(msg.chat.id).await? as usize;
+            let quorum = (members.saturating_sub(1) / 2).max(1);
+            let hour = settings.local_hour(Utc::now());
+
+            let mut req = bot.send_message(
+                msg.chat.id,
+                Msg::ClubStarted(from, per_round, quorum, hour).text(lang),
+            );
+            req.message_thread_id = thread_id;
+            req.await?;
+            state
+                .set_club(msg.chat.id, Club::new(from, per_round, quorum, hour))
+                .await;
+        }
+    };
+    Ok(())
+}
+
+// removes every page sent to the chat in the last 48 hours, except the pinned one
+pub async fn clean(
+    state: Arc<dyn BotStateManager + Send + Sync>,
+    bot: Bot,
+    msg: Message,
+) -> HandlerResult {
+    let lang = lang_of(&*state, msg.chat.id).await;
+    let thread_id = topic_of(&msg);
+    if !sent_by_admin(&bot, &msg).await? {
+        return no_page_in(bot, msg.chat.id, thread_id, Msg::AdminsOnly, lang).await;
+    }
+
+    let pinned = state.pinned(msg.chat.id).await;
+    let to_delete = state
+        .take_all_sent(msg.chat.id)
+        .await
+        .into_iter()
+        .filter(|id| Some(*id) != pinned)
+        .collect();
+    let deleted = bot.delete_messages(msg.chat.id, to_delete).await;
+
+    let mut req = bot.send_message(msg.chat.id, Msg::Cleaned(deleted).text(lang));
+    req.message_thread_id = thread_id;
+    req.await?;
+    Ok(())
+}
+
+// anonymous admins write on behalf of the group itself
+async fn sent_by_admin(bot: &Bot, msg: &Message) -> Result<bool, RequestError> {
+    match msg.sender_chat() {
+        Some(sender_chat) if sender_chat.id == msg.chat.id => Ok(true),
+        _ => is_admin(bot, &msg.chat, msg.from()).await,
+    }
+}
+
+// everybody is an admin of their private chat
+async fn is_admin(bot: &Bot, chat: &Chat, user: Option<&User>) -> Result<bool, RequestError> {
+    let user_id = match user {
+        Some(user) if chat.is_private() => return Ok(user.id.0 as i64 == chat.id.0),
+        Some(user) => user.id,
+        None => return Ok(false),
+    };
+    let admins = bot.get_chat_administrators(chat.id).await?;
+    Ok(admins.iter().any(|m| m.user.id == user_id))
+}
+
 pub async fn jump_menu(
     state: Arc<dyn BotStateManager + Send + Sync>,
     bot: Bot,
@@ -124,12 +979,18 @@ pub async fn jump_menu(
     static BTNS_IN_ROW: isize = 4;
     static PAGES_IN_BTN: isize = PAGES_IN_ROW / BTNS_IN_ROW;
 
+    let lang = lang_of(&*state, msg.chat.id).await;
+    let (to, thread_id) = reader_of(&*state, &msg, msg.from()).await;
+
     let last_idx = state.last_idx().await.unwrap_or(0) as isize;
 
     let btn_on_idx = |idx| InlineKeyboardButton::callback(format!("{}", idx), format!("n-{}", idx));
 
     let btn_rows = (0..=last_idx / PAGES_IN_ROW + 1).fold(
-        vec![vec![InlineKeyboardButton::callback("FIRST", "n-0")]],
+        vec![vec![InlineKeyboardButton::callback(
+            Msg::First.text(lang),
+            "n-0",
+        )]],
         |mut acc, row| {
             let processed = row * PAGES_IN_ROW;
             let rest = last_idx - processed;
@@ -143,7 +1004,7 @@ pub async fn jump_menu(
 
             match btn_row.is_empty() {
                 true => acc.push(vec![InlineKeyboardButton::callback(
-                    "LAST",
+                    Msg::Last.text(lang),
                     format!("n-{}", last_idx),
                 )]),
                 false => acc.push(btn_row),
@@ -153,9 +1014,114 @@ pub async fn jump_menu(
         },
     );
 
-    bot.send_message(msg.chat.id, "JUMP TO")
-        .reply_markup(InlineKeyboardMarkup::new(btn_rows))
-        .await?;
+    let mut req = bot
+        .send_message(to, Msg::JumpTo.text(lang))
+        .reply_markup(InlineKeyboardMarkup::new(btn_rows));
+    req.message_thread_id = thread_id;
+    req.await?;
+
+    Ok(())
+}
+
+pub async fn inline_pages(
+    state: Arc<dyn BotStateManager + Send + Sync>,
+    bot: Bot,
+    q: InlineQuery,
+) -> HandlerResult {
+    // telegram won't take more than 50 results per answer
+    static MAX_RESULTS: usize = 50;
+
+    // there's no chat to take settings from, only the user asking
+    let lang = Lang::from_code(q.from.language_code.as_deref());
+
+    let mut results = vec![];
+    for p in state.search(&q.query, MAX_RESULTS).await {
+        let maybe_file_ids = state.file_ids(p.idx).await;
+        results.extend(inline_result(p, maybe_file_ids, lang));
+    }
+
+    bot.answer_inline_query(q.id, results).await?;
+    Ok(())
+}
+
+fn inline_result(
+    p: KsbdPage,
+    maybe_file_ids: Option<Vec<String>>,
+    lang: Lang,
+) -> Option<InlineQueryResult> {
+    let idx = p.idx;
+    let id = format!("p-{}", idx);
+    // a page with a broken url is left out rather than breaking the whole answer
+    let url = match Url::parse(p.url.as_str()) {
+        Ok(url) => url,
+        Err(e) => {
+            log::warn!("bad url of page {}: {}", idx, e);
+            return None;
+        }
+    };
+    let maybe_thumb = p.imgs.first().and_then(|u| Url::parse(u).ok());
+    let title = format!(
+        "#{} {}",
+        idx,
+        PageToSend::old_page(p).title.unwrap_or_default()
+    );
+    let text = format!("{}\n{}", title, url);
+    // whoever gets the page can go on reading it in the bot
+    let deep_link = Url::parse(&format!(
+        "https://t.me/{}?start=p{}",
+        BOT_NAME.as_str(),
+        idx
+    ))
+    .unwrap();
+    let open_btn = InlineKeyboardMarkup::new(vec![vec![
+        InlineKeyboardButton::url(Msg::Open.text(lang), url.clone()),
+        InlineKeyboardButton::url(Msg::ReadFromHere.text(lang), deep_link),
+    ]]);
+
+    let result = match maybe_file_ids.and_then(|ids| ids.first().cloned()) {
+        Some(file_id) => InlineQueryResult::CachedDocument(
+            InlineQueryResultCachedDocument::new(id, title, file_id)
+                .caption(text)
+                .reply_markup(open_btn),
+        ),
+        None => {
+            let article = InlineQueryResultArticle::new(
+                id,
+                title,
+                InputMessageContent::Text(InputMessageContentText::new(text)),
+            )
+            .url(url)
+            .reply_markup(open_btn);
 
+            InlineQueryResult::Article(match maybe_thumb {
+                Some(thumb) => article.thumb_url(thumb),
+                None => article,
+            })
+        }
+    };
+    Some(result)
+}
+
+// the bot was kicked, or blocked in a private chat
+pub async fn chat_member_update(
+    state: Arc<dyn BotStateManager + Send + Sync>,
+    upd: ChatMemberUpdated,
+) -> HandlerResult {
+    if !upd.new_chat_member.is_present() {
+        log::info!("removed from {}, unsubscribing", upd.chat.id);
+        state.remove_subs(upd.chat.id).await;
+    }
+    Ok(())
+}
+
+// the old group gets a service message pointing to the new supergroup
+pub async fn chat_migration(
+    state: Arc<dyn BotStateManager + Send + Sync>,
+    msg: Message,
+) -> HandlerResult {
+    if let Some(to) = msg.migrate_to_chat_id() {
+        log::info!("{} migrated to {}", msg.chat.id, to);
+        state.migrate_chat(msg.chat.id, to).await;
+    }
     Ok(())
 }
@@ -1,22 +1,54 @@
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use futures::StreamExt;
 use teloxide::prelude::ChatId;
+use teloxide::types::MessageId;
 use tokio::sync::RwLock;
 
 use crate::domain::bot_state::BotState;
+use crate::domain::club_state::Club;
 use crate::domain::ksbd_page::KsbdPage;
+use crate::domain::reread_state::Reread;
+use crate::domain::sent_state::SentPage;
+use crate::domain::settings_state::{ChatSettings, Cleanup};
+use crate::logic::bookmarks_state::BookmarksStateManager;
+use crate::logic::channels_state::ChannelsStateManager;
+use crate::logic::club_state::ClubStateManager;
+use crate::logic::digest_state::DigestStateManager;
+use crate::logic::file_ids_state::FileIdsStateManager;
+use crate::logic::history_state::HistoryStateManager;
+use crate::logic::notes_state::NotesStateManager;
 use crate::logic::pages_state::PagesStateManager;
+use crate::logic::pins_state::PinsStateManager;
+use crate::logic::queue_state::QueueStateManager;
+use crate::logic::reread_state::RereadStateManager;
 use crate::logic::scraper::KsbdScraper;
+use crate::logic::sent_state::SentStateManager;
+use crate::logic::settings_state::SettingsStateManager;
 use crate::logic::subs_state::SubsStateManager;
 
 #[async_trait]
 pub trait BotStateManagerInit {
+    // every piece of state comes with its own storage
+    #[allow(clippy::too_many_arguments)]
     async fn init(
         scraper: impl KsbdScraper + Send + Sync + 'static,
         pages_state_manager: impl PagesStateManager + Clone + Send + Sync + 'static,
         subs_state_manager: impl SubsStateManager + Clone + Send + Sync + 'static,
+        file_ids_state_manager: impl FileIdsStateManager + Clone + Send + Sync + 'static,
+        bookmarks_state_manager: impl BookmarksStateManager + Clone + Send + Sync + 'static,
+        notes_state_manager: impl NotesStateManager + Clone + Send + Sync + 'static,
+        history_state_manager: impl HistoryStateManager + Clone + Send + Sync + 'static,
+        settings_state_manager: impl SettingsStateManager + Clone + Send + Sync + 'static,
+        queue_state_manager: impl QueueStateManager + Clone + Send + Sync + 'static,
+        digest_state_manager: impl DigestStateManager + Clone + Send + Sync + 'static,
+        reread_state_manager: impl RereadStateManager + Clone + Send + Sync + 'static,
+        club_state_manager: impl ClubStateManager + Clone + Send + Sync + 'static,
+        channels_state_manager: impl ChannelsStateManager + Clone + Send + Sync + 'static,
+        pins_state_manager: impl PinsStateManager + Clone + Send + Sync + 'static,
+        sent_state_manager: impl SentStateManager + Clone + Send + Sync + 'static,
     ) -> Self;
 }
 
@@ -25,13 +57,81 @@ pub trait BotStateManager {
     async fn maybe_last(&self) -> Option<KsbdPage>;
     async fn subs_chat_ids(&self) -> Vec<ChatId>;
 
-    async fn add_subs(&self, chat_id: ChatId);
+    async fn add_subs(&self, chat_id: ChatId, thread_id: Option<i32>);
+    async fn thread_of(&self, chat_id: ChatId) -> Option<i32>;
+    // along with everything that would keep being sent to the chat
+    async fn remove_subs(&self, chat_id: ChatId);
+    // everything a group had moves to the supergroup it was turned into
+    async fn migrate_chat(&self, from: ChatId, to: ChatId);
+    async fn position(&self, chat_id: ChatId) -> Option<usize>;
+    async fn set_position(&self, chat_id: ChatId, idx: usize);
     async fn add_pages(&self, pages: Vec<KsbdPage>);
 
     async fn first(&self) -> Option<KsbdPage>;
     async fn last(&self) -> Option<KsbdPage>;
     async fn by_idx(&self, idx: usize) -> Option<KsbdPage>;
     async fn last_idx(&self) -> Option<usize>;
+    async fn random(&self) -> Option<KsbdPage>;
+    async fn on_this_day(&self, today: DateTime<Utc>) -> Vec<KsbdPage>;
+    async fn without_published(&self) -> Vec<(usize, String)>;
+    async fn set_published(&self, dates: Vec<(usize, Option<DateTime<Utc>>)>);
+    async fn search(&self, query: &str, limit: usize) -> Vec<KsbdPage>;
+
+    async fn file_ids(&self, idx: usize) -> Option<Vec<String>>;
+    async fn add_file_ids(&self, idx: usize, ids: Vec<String>);
+
+    async fn bookmarks(&self, chat_id: ChatId) -> Vec<usize>;
+    async fn add_bookmark(&self, chat_id: ChatId, idx: usize) -> bool;
+    async fn remove_bookmark(&self, chat_id: ChatId, idx: usize);
+
+    async fn notes(&self, chat_id: ChatId, idx: usize) -> Vec<String>;
+    async fn all_notes(&self, chat_id: ChatId) -> Vec<(usize, Vec<String>)>;
+    async fn add_note(&self, chat_id: ChatId, idx: usize, note: String);
+
+    async fn history(&self, chat_id: ChatId) -> Vec<usize>;
+    async fn record_history(&self, chat_id: ChatId, idx: usize);
+    async fn history_back_to(&self, chat_id: ChatId, idx: usize);
+
+    async fn settings(&self, chat_id: ChatId) -> ChatSettings;
+    async fn set_settings(&self, chat_id: ChatId, settings: ChatSettings);
+    async fn init_settings(&self, chat_id: ChatId, settings: ChatSettings);
+
+    async fn queue_pages(&self, chat_id: ChatId, idxs: Vec<usize>);
+    async fn take_queued(&self, chat_id: ChatId) -> Vec<usize>;
+    async fn queued_chat_ids(&self) -> Vec<ChatId>;
+
+    async fn add_to_digest(&self, chat_id: ChatId, idxs: Vec<usize>);
+    async fn take_digest(&self, chat_id: ChatId) -> Vec<usize>;
+    async fn digest_chat_ids(&self) -> Vec<ChatId>;
+
+    async fn reread(&self, chat_id: ChatId) -> Option<Reread>;
+    async fn set_reread(&self, chat_id: ChatId, reread: Reread);
+    async fn reread_chat_ids(&self) -> Vec<ChatId>;
+
+    async fn club(&self, chat_id: ChatId) -> Option<Club>;
+    async fn set_club(&self, chat_id: ChatId, club: Club);
+    async fn remove_club(&self, chat_id: ChatId) -> bool;
+    async fn vote_club(&self, chat_id: ChatId, user_id: u64, next: usize) -> Option<Club>;
+    async fn club_chat_ids(&self) -> Vec<ChatId>;
+
+    async fn channel_posted(&self, channel: &str) -> Option<usize>;
+    async fn set_channel_posted(&self, channel: &str, idx: usize);
+    async fn channel_albums_posted(&self, channel: &str) -> usize;
+    async fn set_channel_albums_posted(&self, channel: &str, n: usize);
+
+    async fn pinned(&self, chat_id: ChatId) -> Option<MessageId>;
+    async fn set_pinned(&self, chat_id: ChatId, message_id: MessageId);
+
+    async fn record_sent(&self, chat_id: ChatId, page: SentPage);
+    async fn take_expired_sent(
+        &self,
+        chat_id: ChatId,
+        cleanup: Cleanup,
+        pinned: Option<MessageId>,
+        now: DateTime<Utc>,
+    ) -> Vec<MessageId>;
+    async fn take_all_sent(&self, chat_id: ChatId) -> Vec<MessageId>;
+    async fn sent_chat_ids(&self) -> Vec<ChatId>;
 }
 
 #[derive(Clone)]
@@ -39,14 +139,39 @@ pub struct BotStateManagerImpl {
     inner_state: Arc<RwLock<BotState>>,
     pages_state_manager: Arc<dyn PagesStateManager + Send + Sync>,
     subs_state_manager: Arc<dyn SubsStateManager + Send + Sync>,
+    file_ids_state_manager: Arc<dyn FileIdsStateManager + Send + Sync>,
+    bookmarks_state_manager: Arc<dyn BookmarksStateManager + Send + Sync>,
+    notes_state_manager: Arc<dyn NotesStateManager + Send + Sync>,
+    history_state_manager: Arc<dyn HistoryStateManager + Send + Sync>,
+    settings_state_manager: Arc<dyn SettingsStateManager + Send + Sync>,
+    queue_state_manager: Arc<dyn QueueStateManager + Send + Sync>,
+    digest_state_manager: Arc<dyn DigestStateManager + Send + Sync>,
+    reread_state_manager: Arc<dyn RereadStateManager + Send + Sync>,
+    club_state_manager: Arc<dyn ClubStateManager + Send + Sync>,
+    channels_state_manager: Arc<dyn ChannelsStateManager + Send + Sync>,
+    pins_state_manager: Arc<dyn PinsStateManager + Send + Sync>,
+    sent_state_manager: Arc<dyn SentStateManager + Send + Sync>,
 }
 
 #[async_trait]
 impl BotStateManagerInit for BotStateManagerImpl {
+    #[allow(clippy::too_many_arguments)]
     async fn init(
         scraper: impl KsbdScraper + Send + Sync + 'static,
         pages_state_manager: impl PagesStateManager + Clone + Send + Sync + 'static,
         subs_state_manager: impl SubsStateManager + Clone + Send + Sync + 'static,
+        file_ids_state_manager: impl FileIdsStateManager + Clone + Send + Sync + 'static,
+        bookmarks_state_manager: impl BookmarksStateManager + Clone + Send + Sync + 'static,
+        notes_state_manager: impl NotesStateManager + Clone + Send + Sync + 'static,
+        history_state_manager: impl HistoryStateManager + Clone + Send + Sync + 'static,
+        settings_state_manager: impl SettingsStateManager + Clone + Send + Sync + 'static,
+        queue_state_manager: impl QueueStateManager + Clone + Send + Sync + 'static,
+        digest_state_manager: impl DigestStateManager + Clone + Send + Sync + 'static,
+        reread_state_manager: impl RereadStateManager + Clone + Send + Sync + 'static,
+        club_state_manager: impl ClubStateManager + Clone + Send + Sync + 'static,
+        channels_state_manager: impl ChannelsStateManager + Clone + Send + Sync + 'static,
+        pins_state_manager: impl PinsStateManager + Clone + Send + Sync + 'static,
+        sent_state_manager: impl SentStateManager + Clone + Send + Sync + 'static,
     ) -> Self {
         let pages_state = pages_state_manager.clone().load_pages_state().await;
         if let Some((idx, url)) = pages_state.start_from() {
@@ -64,19 +189,67 @@ impl BotStateManagerInit for BotStateManagerImpl {
         }
         let pages_state = pages_state_manager.load_pages_state().await;
         let subs_state = subs_state_manager.load_subs_state().await;
+        let file_ids_state = file_ids_state_manager.load_file_ids_state().await;
+        let bookmarks_state = bookmarks_state_manager.load_bookmarks_state().await;
+        let notes_state = notes_state_manager.load_notes_state().await;
+        let history_state = history_state_manager.load_history_state().await;
+        let settings_state = settings_state_manager.load_settings_state().await;
+        let queue_state = queue_state_manager.load_queue_state().await;
+        let digest_state = digest_state_manager.load_digest_state().await;
+        let reread_state = reread_state_manager.load_reread_state().await;
+        let club_state = club_state_manager.load_club_state().await;
+        let channels_state = channels_state_manager.load_channels_state().await;
+        let pins_state = pins_state_manager.load_pins_state().await;
+        let sent_state = sent_state_manager.load_sent_state().await;
 
         let inner_state = Arc::new(RwLock::new(BotState {
             pages: pages_state,
             subscribers: subs_state,
+            file_ids: file_ids_state,
+            bookmarks: bookmarks_state,
+            notes: notes_state,
+            history: history_state,
+            settings: settings_state,
+            queue: queue_state,
+            digest: digest_state,
+            reread: reread_state,
+            clubs: club_state,
+            channels: channels_state,
+            pins: pins_state,
+            sent: sent_state,
         }));
 
         let pages_state_manager = Arc::new(pages_state_manager.clone());
         let subs_state_manager = Arc::new(subs_state_manager.clone());
+        let file_ids_state_manager = Arc::new(file_ids_state_manager.clone());
+        let bookmarks_state_manager = Arc::new(bookmarks_state_manager.clone());
+        let notes_state_manager = Arc::new(notes_state_manager.clone());
+        let history_state_manager = Arc::new(history_state_manager.clone());
+        let settings_state_manager = Arc::new(settings_state_manager.clone());
+        let queue_state_manager = Arc::new(queue_state_manager.clone());
+        let digest_state_manager = Arc::new(digest_state_manager.clone());
+        let reread_state_manager = Arc::new(reread_state_manager.clone());
+        let club_state_manager = Arc::new(club_state_manager.clone());
+        let channels_state_manager = Arc::new(channels_state_manager.clone());
+        let pins_state_manager = Arc::new(pins_state_manager.clone());
+        let sent_state_manager = Arc::new(sent_state_manager.clone());
 
         BotStateManagerImpl {
             inner_state,
             pages_state_manager,
             subs_state_manager,
+            file_ids_state_manager,
+            bookmarks_state_manager,
+            notes_state_manager,
+            history_state_manager,
+            settings_state_manager,
+            queue_state_manager,
+            digest_state_manager,
+            reread_state_manager,
+            club_state_manager,
+            channels_state_manager,
+            pins_state_manager,
+            sent_state_manager,
         }
     }
 }
@@ -91,14 +264,122 @@ impl BotStateManager for BotStateManagerImpl {
         self.inner_state.read().await.subscribers.clone().chat_ids()
     }
 
-    async fn add_subs(&self, chat_id: ChatId) {
+    async fn add_subs(&self, chat_id: ChatId, thread_id: Option<i32>) {
         let mut state_to_write = self.inner_state.write().await;
         state_to_write.subscribers.add(chat_id.0);
+        state_to_write.subscribers.set_thread(chat_id.0, thread_id);
         self.subs_state_manager
             .save_subs_state(&state_to_write.subscribers)
             .await
     }
 
+    async fn thread_of(&self, chat_id: ChatId) -> Option<i32> {
+        let state = self.inner_state.read().await;
+        state.subscribers.thread(chat_id.0)
+    }
+
+    async fn remove_subs(&self, chat_id: ChatId) {
+        let mut state_to_write = self.inner_state.write().await;
+        if state_to_write.subscribers.remove(chat_id.0) {
+            self.subs_state_manager
+                .save_subs_state(&state_to_write.subscribers)
+                .await
+        }
+        if state_to_write.clubs.remove(chat_id) {
+            self.club_state_manager
+                .save_club_state(&state_to_write.clubs)
+                .await
+        }
+        if state_to_write.reread.remove(chat_id) {
+            self.reread_state_manager
+                .save_reread_state(&state_to_write.reread)
+                .await
+        }
+        if !state_to_write.queue.take(chat_id).is_empty() {
+            self.queue_state_manager
+                .save_queue_state(&state_to_write.queue)
+                .await
+        }
+        if !state_to_write.digest.take(chat_id).is_empty() {
+            self.digest_state_manager
+                .save_digest_state(&state_to_write.digest)
+                .await
+        }
+    }
+
+    async fn migrate_chat(&self, from: ChatId, to: ChatId) {
+        let mut state_to_write = self.inner_state.write().await;
+        if state_to_write.subscribers.migrate(from.0, to.0) {
+            self.subs_state_manager
+                .save_subs_state(&state_to_write.subscribers)
+                .await
+        }
+        if state_to_write.settings.migrate(from, to) {
+            self.settings_state_manager
+                .save_settings_state(&state_to_write.settings)
+                .await
+        }
+        if state_to_write.bookmarks.migrate(from, to) {
+            self.bookmarks_state_manager
+                .save_bookmarks_state(&state_to_write.bookmarks)
+                .await
+        }
+        if state_to_write.notes.migrate(from, to) {
+            self.notes_state_manager
+                .save_notes_state(&state_to_write.notes)
+                .await
+        }
+        if state_to_write.history.migrate(from, to) {
+            self.history_state_manager
+                .save_history_state(&state_to_write.history)
+                .await
+        }
+        if state_to_write.queue.migrate(from, to) {
+            self.queue_state_manager
+                .save_queue_state(&state_to_write.queue)
+                .await
+        }
+        if state_to_write.digest.migrate(from, to) {
+            self.digest_state_manager
+                .save_digest_state(&state_to_write.digest)
+                .await
+        }
+        if state_to_write.reread.migrate(from, to) {
+            self.reread_state_manager
+                .save_reread_state(&state_to_write.reread)
+                .await
+        }
+        if state_to_write.clubs.migrate(from, to) {
+            self.club_state_manager
+                .save_club_state(&state_to_write.clubs)
+                .await
+        }
+        if state_to_write.pins.migrate(from, to) {
+            self.pins_state_manager
+                .save_pins_state(&state_to_write.pins)
+                .await
+        }
+        if state_to_write.sent.migrate(from, to) {
+            self.sent_state_manager
+                .save_sent_state(&state_to_write.sent)
+                .await
+        }
+    }
+
+    async fn position(&self, chat_id: ChatId) -> Option<usize> {
+        let state = self.inner_state.read().await;
+        state.subscribers.last_idx(chat_id.0)
+    }
+
+    async fn set_position(&self, chat_id: ChatId, idx: usize) {
+        let mut state_to_write = self.inner_state.write().await;
+        if state_to_write.subscribers.set_last_idx(chat_id.0, idx) {
+            self.subs_state_manager
+                .save_subs_state(&state_to_write.subscribers)
+                .await
+        }
+    }
+
     async fn add_pages(&self, pages: Vec<KsbdPage>) {
         let mut state_to_write = self.inner_state.write().await;
         state_to_write.pages.add_pages(pages.clone());
@@ -126,4 +407,318 @@ impl BotStateManager for BotStateManagerImpl {
         let state = self.inner_state.read().await;
         state.pages.last().map(|l| l.idx)
     }
+
+    async fn random(&self) -> Option<KsbdPage> {
+        let state = self.inner_state.read().await;
+        state.pages.random().cloned()
+    }
+
+    async fn on_this_day(&self, today: DateTime<Utc>) -> Vec<KsbdPage> {
+        let state = self.inner_state.read().await;
+        state
+            .pages
+            .on_this_day(today)
+            .into_iter()
+            .cloned()
+            .collect()
+    }
+
+    async fn without_published(&self) -> Vec<(usize, String)> {
+        let state = self.inner_state.read().await;
+        state.pages.without_published()
+    }
+
+    async fn set_published(&self, dates: Vec<(usize, Option<DateTime<Utc>>)>) {
+        let mut state_to_write = self.inner_state.write().await;
+        for (idx, published) in dates {
+            state_to_write.pages.set_published(idx, published);
+        }
+        self.pages_state_manager
+            .save_pages_state(&state_to_write.pages)
+            .await
+    }
+
+    async fn search(&self, query: &str, limit: usize) -> Vec<KsbdPage> {
+        let state = self.inner_state.read().await;
+        state
+            .pages
+            .search(query, limit)
+            .into_iter()
+            .cloned()
+            .collect()
+    }
+
+    async fn file_ids(&self, idx: usize) -> Option<Vec<String>> {
+        let state = self.inner_state.read().await;
+        state.file_ids.by_idx(idx).cloned()
+    }
+
+    async fn add_file_ids(&self, idx: usize, ids: Vec<String>) {
+        let mut state_to_write = self.inner_state.write().await;
+        state_to_write.file_ids.add(idx, ids);
+        self.file_ids_state_manager
+            .save_file_ids_state(&state_to_write.file_ids)
+            .await
+    }
+
+    async fn bookmarks(&self, chat_id: ChatId) -> Vec<usize> {
+        let state = self.inner_state.read().await;
+        state.bookmarks.by_chat(chat_id)
+    }
+
+    async fn add_bookmark(&self, chat_id: ChatId, idx: usize) -> bool {
+        let mut state_to_write = self.inner_state.write().await;
+        let added = state_to_write.bookmarks.add(chat_id, idx);
+        self.bookmarks_state_manager
+            .save_bookmarks_state(&state_to_write.bookmarks)
+            .await;
+        added
+    }
+
+    async fn remove_bookmark(&self, chat_id: ChatId, idx: usize) {
+        let mut state_to_write = self.inner_state.write().await;
+        state_to_write.bookmarks.remove(chat_id, idx);
+        self.bookmarks_state_manager
+            .save_bookmarks_state(&state_to_write.bookmarks)
+            .await
+    }
+
+    async fn notes(&self, chat_id: ChatId, idx: usize) -> Vec<String> {
+        let state = self.inner_state.read().await;
+        state.notes.by_page(chat_id, idx)
+    }
+
+    async fn all_notes(&self, chat_id: ChatId) -> Vec<(usize, Vec<String>)> {
+        let state = self.inner_state.read().await;
+        state.notes.by_chat(chat_id)
+    }
+
+    async fn add_note(&self, chat_id: ChatId, idx: usize, note: String) {
+        let mut state_to_write = self.inner_state.write().await;
+        state_to_write.notes.add(chat_id, idx, note);
+        self.notes_state_manager
+            .save_notes_state(&state_to_write.notes)
+            .await
+    }
+
+    async fn history(&self, chat_id: ChatId) -> Vec<usize> {
+        let state = self.inner_state.read().await;
+        state.history.by_chat(chat_id)
+    }
+
+    async fn record_history(&self, chat_id: ChatId, idx: usize) {
+        let mut state_to_write = self.inner_state.write().await;
+        state_to_write.history.record(chat_id, idx);
+        self.history_state_manager
+            .save_history_state(&state_to_write.history)
+            .await
+    }
+
+    async fn history_back_to(&self, chat_id: ChatId, idx: usize) {
+        let mut state_to_write = self.inner_state.write().await;
+        state_to_write.history.back_to(chat_id, idx);
+        self.history_state_manager
+            .save_history_state(&state_to_write.history)
+            .await
+    }
+
+    async fn settings(&self, chat_id: ChatId) -> ChatSettings {
+        let state = self.inner_state.read().await;
+        state.settings.by_chat(chat_id)
+    }
+
+    async fn set_settings(&self, chat_id: ChatId, settings: ChatSettings) {
+        let mut state_to_write = self.inner_state.write().await;
+        state_to_write.settings.set(chat_id, settings);
+        self.settings_state_manager
+            .save_settings_state(&state_to_write.settings)
+            .await
+    }
+
+    async fn init_settings(&self, chat_id: ChatId, settings: ChatSettings) {
+        let mut state_to_write = self.inner_state.write().await;
+        if state_to_write.settings.init(chat_id, settings) {
+            self.settings_state_manager
+                .save_settings_state(&state_to_write.settings)
+                .await
+        }
+    }
+
+    async fn queue_pages(&self, chat_id: ChatId, idxs: Vec<usize>) {
+        let mut state_to_write = self.inner_state.write().await;
+        state_to_write.queue.add(chat_id, idxs);
+        self.queue_state_manager
+            .save_queue_state(&state_to_write.queue)
+            .await
+    }
+
+    async fn take_queued(&self, chat_id: ChatId) -> Vec<usize> {
+        let mut state_to_write = self.inner_state.write().await;
+        let idxs = state_to_write.queue.take(chat_id);
+        self.queue_state_manager
+            .save_queue_state(&state_to_write.queue)
+            .await;
+        idxs
+    }
+
+    async fn queued_chat_ids(&self) -> Vec<ChatId> {
+        let state = self.inner_state.read().await;
+        state.queue.chat_ids()
+    }
+
+    async fn add_to_digest(&self, chat_id: ChatId, idxs: Vec<usize>) {
+        let mut state_to_write = self.inner_state.write().await;
+        state_to_write.digest.add(chat_id, idxs);
+        self.digest_state_manager
+            .save_digest_state(&state_to_write.digest)
+            .await
+    }
+
+    async fn take_digest(&self, chat_id: ChatId) -> Vec<usize> {
+        let mut state_to_write = self.inner_state.write().await;
+        let idxs = state_to_write.digest.take(chat_id);
+        self.digest_state_manager
+            .save_digest_state(&state_to_write.digest)
+            .await;
+        idxs
+    }
+
+    async fn digest_chat_ids(&self) -> Vec<ChatId> {
+        let state = self.inner_state.read().await;
+        state.digest.chat_ids()
+    }
+
+    async fn reread(&self, chat_id: ChatId) -> Option<Reread> {
+        let state = self.inner_state.read().await;
+        state.reread.by_chat(chat_id)
+    }
+
+    async fn set_reread(&self, chat_id: ChatId, reread: Reread) {
+        let mut state_to_write = self.inner_state.write().await;
+        state_to_write.reread.set(chat_id, reread);
+        self.reread_state_manager
+            .save_reread_state(&state_to_write.reread)
+            .await
+    }
+
+    async fn reread_chat_ids(&self) -> Vec<ChatId> {
+        let state = self.inner_state.read().await;
+        state.reread.chat_ids()
+    }
+
+    async fn club(&self, chat_id: ChatId) -> Option<Club> {
+        let state = self.inner_state.read().await;
+        state.clubs.by_chat(chat_id)
+    }
+
+    async fn set_club(&self, chat_id: ChatId, club: Club) {
+        let mut state_to_write = self.inner_state.write().await;
+        state_to_write.clubs.set(chat_id, club);
+        self.club_state_manager
+            .save_club_state(&state_to_write.clubs)
+            .await
+    }
+
+    async fn remove_club(&self, chat_id: ChatId) -> bool {
+        let mut state_to_write = self.inner_state.write().await;
+        let removed = state_to_write.clubs.remove(chat_id);
+        self.club_state_manager
+            .save_club_state(&state_to_write.clubs)
+            .await;
+        removed
+    }
+
+    async fn vote_club(&self, chat_id: ChatId, user_id: u64, next: usize) -> Option<Club> {
+        let mut state_to_write = self.inner_state.write().await;
+        let club = state_to_write.clubs.vote(chat_id, user_id, next);
+        self.club_state_manager
+            .save_club_state(&state_to_write.clubs)
+            .await;
+        club
+    }
+
+    async fn club_chat_ids(&self) -> Vec<ChatId> {
+        let state = self.inner_state.read().await;
+        state.clubs.chat_ids()
+    }
+
+    async fn channel_posted(&self, channel: &str) -> Option<usize> {
+        let state = self.inner_state.read().await;
+        state.channels.last_posted(channel)
+    }
+
+    async fn set_channel_posted(&self, channel: &str, idx: usize) {
+        let mut state_to_write = self.inner_state.write().await;
+        state_to_write.channels.set_posted(channel, idx);
+        self.channels_state_manager
+            .save_channels_state(&state_to_write.channels)
+            .await
+    }
+
+    async fn channel_albums_posted(&self, channel: &str) -> usize {
+        let state = self.inner_state.read().await;
+        state.channels.albums_posted(channel)
+    }
+
+    async fn set_channel_albums_posted(&self, channel: &str, n: usize) {
+        let mut state_to_write = self.inner_state.write().await;
+        state_to_write.channels.set_albums_posted(channel, n);
+        self.channels_state_manager
+            .save_channels_state(&state_to_write.channels)
+            .await
+    }
+
+    async fn pinned(&self, chat_id: ChatId) -> Option<MessageId> {
+        let state = self.inner_state.read().await;
+        state.pins.by_chat(chat_id)
+    }
+
+    async fn set_pinned(&self, chat_id: ChatId, message_id: MessageId) {
+        let mut state_to_write = self.inner_state.write().await;
+        state_to_write.pins.set(chat_id, message_id);
+        self.pins_state_manager
+            .save_pins_state(&state_to_write.pins)
+            .await
+    }
+
+    async fn record_sent(&self, chat_id: ChatId, page: SentPage) {
+        let mut state_to_write = self.inner_state.write().await;
+        state_to_write.sent.add(chat_id, page);
+        self.sent_state_manager
+            .save_sent_state(&state_to_write.sent)
+            .await
+    }
+
+    async fn take_expired_sent(
+        &self,
+        chat_id: ChatId,
+        cleanup: Cleanup,
+        pinned: Option<MessageId>,
+        now: DateTime<Utc>,
+    ) -> Vec<MessageId> {
+        let mut state_to_write = self.inner_state.write().await;
+        let expired = state_to_write
+            .sent
+            .take_expired(chat_id, cleanup, pinned, now);
+        if !expired.is_empty() {
+            self.sent_state_manager
+                .save_sent_state(&state_to_write.sent)
+                .await
+        }
+        expired
+    }
+
+    async fn take_all_sent(&self, chat_id: ChatId) -> Vec<MessageId> {
+        let mut state_to_write = self.inner_state.write().await;
+        let sent = state_to_write.sent.take_all(chat_id);
+        self.sent_state_manager
+            .save_sent_state(&state_to_write.sent)
+            .await;
+        sent
+    }
+
+    async fn sent_chat_ids(&self) -> Vec<ChatId> {
+        let state = self.inner_state.read().await;
+        state.sent.chat_ids()
+    }
 }
@@ -0,0 +1,43 @@
+use std::path;
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use lazy_static::lazy_static;
+use tokio::fs;
+
+use crate::cfg::DATA_PATH;
+use crate::domain::channels_state::ChannelsState;
+
+lazy_static! {
+    static ref STATE_PATH: String = format!("{}/channels_state.txt", DATA_PATH.as_str());
+}
+
+#[async_trait]
+pub trait ChannelsStateManager {
+    async fn load_channels_state(&self) -> ChannelsState;
+    async fn save_channels_state(&self, state: &ChannelsState);
+}
+
+// prod implementation
+#[derive(Clone, Copy)]
+pub struct ChannelsStateManagerImpl {}
+
+#[async_trait]
+impl ChannelsStateManager for ChannelsStateManagerImpl {
+    async fn load_channels_state(&self) -> ChannelsState {
+        match path::Path::new(STATE_PATH.as_str()).exists() {
+            false => ChannelsState::default(),
+            true => fs::read_to_string(STATE_PATH.as_str())
+                .await
+                .map_err(|e| e.to_string())
+                .and_then(|s| ChannelsState::from_str(s.as_str()))
+                .unwrap(),
+        }
+    }
+
+    async fn save_channels_state(&self, state: &ChannelsState) {
+        fs::write(STATE_PATH.as_str(), state.to_string())
+            .await
+            .unwrap()
+    }
+}
@@ -0,0 +1,43 @@
+use std::path;
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use lazy_static::lazy_static;
+use tokio::fs;
+
+use crate::cfg::DATA_PATH;
+use crate::domain::club_state::ClubState;
+
+lazy_static! {
+    static ref STATE_PATH: String = format!("{}/club_state.txt", DATA_PATH.as_str());
+}
+
+#[async_trait]
+pub trait ClubStateManager {
+    async fn load_club_state(&self) -> ClubState;
+    async fn save_club_state(&self, state: &ClubState);
+}
+
+// prod implementation
+#[derive(Clone, Copy)]
+pub struct ClubStateManagerImpl {}
+
+#[async_trait]
+impl ClubStateManager for ClubStateManagerImpl {
+    async fn load_club_state(&self) -> ClubState {
+        match path::Path::new(STATE_PATH.as_str()).exists() {
+            false => ClubState::default(),
+            true => fs::read_to_string(STATE_PATH.as_str())
+                .await
+                .map_err(|e| e.to_string())
+                .and_then(|s| ClubState::from_str(s.as_str()))
+                .unwrap(),
+        }
+    }
+
+    async fn save_club_state(&self, state: &ClubState) {
+        fs::write(STATE_PATH.as_str(), state.to_string())
+            .await
+            .unwrap()
+    }
+}
@@ -0,0 +1,43 @@
+use std::path;
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use lazy_static::lazy_static;
+use tokio::fs;
+
+use crate::cfg::DATA_PATH;
+use crate::domain::file_ids_state::FileIdsState;
+
+lazy_static! {
+    static ref STATE_PATH: String = format!("{}/file_ids_state.txt", DATA_PATH.as_str());
+}
+
+#[async_trait]
+pub trait FileIdsStateManager {
+    async fn load_file_ids_state(&self) -> FileIdsState;
+    async fn save_file_ids_state(&self, state: &FileIdsState);
+}
+
+// prod implementation
+#[derive(Clone, Copy)]
+pub struct FileIdsStateManagerImpl {}
+
+#[async_trait]
+impl FileIdsStateManager for FileIdsStateManagerImpl {
+    async fn load_file_ids_state(&self) -> FileIdsState {
+        match path::Path::new(STATE_PATH.as_str()).exists() {
+            false => FileIdsState::default(),
+            true => fs::read_to_string(STATE_PATH.as_str())
+                .await
+                .map_err(|e| e.to_string())
+                .and_then(|s| FileIdsState::from_str(s.as_str()))
+                .unwrap(),
+        }
+    }
+
+    async fn save_file_ids_state(&self, state: &FileIdsState) {
+        fs::write(STATE_PATH.as_str(), state.to_string())
+            .await
+            .unwrap()
+    }
+}
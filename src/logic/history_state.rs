@@ -0,0 +1,43 @@
+use std::path;
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use lazy_static::lazy_static;
+use tokio::fs;
+
+use crate::cfg::DATA_PATH;
+use crate::domain::history_state::HistoryState;
+
+lazy_static! {
+    static ref STATE_PATH: String = format!("{}/history_state.txt", DATA_PATH.as_str());
+}
+
+#[async_trait]
+pub trait HistoryStateManager {
+    async fn load_history_state(&self) -> HistoryState;
+    async fn save_history_state(&self, state: &HistoryState);
+}
+
+// prod implementation
+#[derive(Clone, Copy)]
+pub struct HistoryStateManagerImpl {}
+
+#[async_trait]
+impl HistoryStateManager for HistoryStateManagerImpl {
+    async fn load_history_state(&self) -> HistoryState {
+        match path::Path::new(STATE_PATH.as_str()).exists() {
+            false => HistoryState::default(),
+            true => fs::read_to_string(STATE_PATH.as_str())
+                .await
+                .map_err(|e| e.to_string())
+                .and_then(|s| HistoryState::from_str(s.as_str()))
+                .unwrap(),
+        }
+    }
+
+    async fn save_history_state(&self, state: &HistoryState) {
+        fs::write(STATE_PATH.as_str(), state.to_string())
+            .await
+            .unwrap()
+    }
+}
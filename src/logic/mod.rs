@@ -1,8 +1,24 @@
+pub mod bookmarks_state;
 pub mod bot_flow;
 pub mod bot_state;
+pub mod channels_state;
+pub mod club_state;
+pub mod digest_state;
+pub mod file_ids_state;
+pub mod history_state;
+pub mod notes_state;
 pub mod page_sender;
 pub mod pages_state;
+pub mod pins_state;
+pub mod queue_state;
+pub mod reread_state;
+pub mod scheduler;
 pub mod scraper;
+pub mod sent_state;
+pub mod settings_state;
 pub mod subs_state;
+pub mod translator;
 
 pub type HandlerResult = Result<(), Box<dyn std::error::Error + Send + Sync>>;
+pub type SentResult =
+    Result<Vec<teloxide::types::Message>, Box<dyn std::error::Error + Send + Sync>>;
@@ -0,0 +1,43 @@
+use std::path;
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use lazy_static::lazy_static;
+use tokio::fs;
+
+use crate::cfg::DATA_PATH;
+use crate::domain::notes_state::NotesState;
+
+lazy_static! {
+    static ref STATE_PATH: String = format!("{}/notes_state.txt", DATA_PATH.as_str());
+}
+
+#[async_trait]
+pub trait NotesStateManager {
+    async fn load_notes_state(&self) -> NotesState;
+    async fn save_notes_state(&self, state: &NotesState);
+}
+
+// prod implementation
+#[derive(Clone, Copy)]
+pub struct NotesStateManagerImpl {}
+
+#[async_trait]
+impl NotesStateManager for NotesStateManagerImpl {
+    async fn load_notes_state(&self) -> NotesState {
+        match path::Path::new(STATE_PATH.as_str()).exists() {
+            false => NotesState::default(),
+            true => fs::read_to_string(STATE_PATH.as_str())
+                .await
+                .map_err(|e| e.to_string())
+                .and_then(|s| NotesState::from_str(s.as_str()))
+                .unwrap(),
+        }
+    }
+
+    async fn save_notes_state(&self, state: &NotesState) {
+        fs::write(STATE_PATH.as_str(), state.to_string())
+            .await
+            .unwrap()
+    }
+}
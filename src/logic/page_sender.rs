@@ -2,121 +2,510 @@ use async_trait::async_trait;
 use reqwest::Url;
 use teloxide::prelude::*;
 use teloxide::types::*;
-use teloxide::Bot;
+use teloxide::{ApiError, Bot, RequestError};
 
+use crate::cfg::LIBRETRANSLATE_URL;
+use crate::domain::club_state::Club;
+use crate::domain::html_text;
+use crate::domain::html_text::{utf16_len, MAX_CAPTION_LEN, MAX_TEXT_LEN};
+use crate::domain::i18n::{Lang, Msg};
 use crate::domain::page_to_send::PageToSend;
-use crate::logic::HandlerResult;
+use crate::domain::settings_state::{ChatSettings, DeliveryFormat};
+use crate::logic::translator::GoogleLinkTranslator;
+use crate::logic::SentResult;
 
 #[async_trait]
 pub trait PageSender {
-    async fn send_full_page(&self, p: PageToSend, to: ChatId) -> HandlerResult;
+    async fn send_full_page(
+        &self,
+        p: PageToSend,
+        to: ChatId,
+        settings: &ChatSettings,
+    ) -> SentResult;
+
+    async fn send_digest(
+        &self,
+        pages: Vec<PageToSend>,
+        to: ChatId,
+        settings: &ChatSettings,
+    ) -> SentResult;
+
+    async fn send_burst(
+        &self,
+        first: &PageToSend,
+        count: usize,
+        to: ChatId,
+        settings: &ChatSettings,
+    ) -> SentResult;
+
+    async fn send_club_progress(
+        &self,
+        club: &Club,
+        last_idx: usize,
+        to: ChatId,
+        thread_id: Option<i32>,
+        settings: &ChatSettings,
+    ) -> SentResult;
+
+    async fn publish_album(&self, p: &PageToSend, n: usize, to: Recipient) -> SentResult;
+
+    async fn pin(
+        &self,
+        to: ChatId,
+        message_id: MessageId,
+        replaced: Option<MessageId>,
+    ) -> Result<(), RequestError>;
+
+    async fn delete_messages(&self, to: ChatId, ids: Vec<MessageId>) -> usize;
 }
 
 #[async_trait]
 impl PageSender for Bot {
-    async fn send_full_page(&self, p: PageToSend, to: ChatId) -> HandlerResult {
+    async fn send_full_page(
+        &self,
+        p: PageToSend,
+        to: ChatId,
+        settings: &ChatSettings,
+    ) -> SentResult {
         log::info!("page {} requested by {}", p.idx, to);
 
+        let mut sent = vec![];
+
+        let compact = match settings.compact && !settings.reader_mode {
+            true => compact_caption(&p, settings.lang),
+            false => None,
+        };
+        if let Some(caption) = compact {
+            let imgs = p.img_files();
+            // compact pages have at least one img, hence unwrap
+            let (last, first) = imgs.as_slice().split_last().unwrap();
+
+            for img_file in first {
+                sent.push(send_img(self, to, img_file, &p, settings, None, None).await?);
+            }
+
+            let mut rows = vec![];
+            if !p.text.is_empty() {
+                rows.push(translate_btn(&caption, p.idx, settings));
+            }
+            rows.push(nav_btns(&p, settings.lang));
+            rows.push(site_btns(&p, settings.lang));
+            let markup = InlineKeyboardMarkup::new(rows);
+
+            let captioned = send_img(
+                self,
+                to,
+                last,
+                &p,
+                settings,
+                Some(markup.clone()),
+                Some(caption.clone()),
+            )
+            .await;
+            let plain_caption = html_text::escape(&html_text::strip_tags(&caption));
+            sent.push(match captioned {
+                Err(e) if is_parse_error(&e) => {
+                    send_img(
+                        self,
+                        to,
+                        last,
+                        &p,
+                        settings,
+                        Some(markup),
+                        Some(plain_caption),
+                    )
+                    .await?
+                }
+                captioned => captioned?,
+            });
+
+            return Ok(sent);
+        }
+
         if p.is_new {
-            self.send_message(to, "🎉🎉🎉 GREAT NEWS!! NEW PAGE IS ON THE WAY 🎉🎉🎉")
-                .await?;
+            sent.push(send_txt(self, to, Msg::GreatNews.text(settings.lang), &p).await?);
         }
 
         let imgs = p.img_files().clone();
-        if let Some(title) = &p.title {
-            self.send_message(to, title).await?;
+        if let Some(header) = p.header().filter(|_| !settings.reader_mode) {
+            sent.push(send_txt(self, to, header, &p).await?);
         }
 
-        match &p.text.is_empty() {
-            true => {
-                // it has to have at least one img, hence unwrap
-                let (last, first) = imgs.as_slice().split_last().unwrap();
+        let txts = match settings.reader_mode {
+            true => vec![],
+            false => html_text::split(&p.text, MAX_TEXT_LEN),
+        };
+
+        match txts.split_last() {
+            None => {
+                let nav_markup = InlineKeyboardMarkup::new(vec![
+                    nav_btns(&p, settings.lang),
+                    site_btns(&p, settings.lang),
+                ]);
+
+                // no images either, what's left is the title and the link
+                let Some((last, first)) = imgs.as_slice().split_last() else {
+                    let txt = match &p.title {
+                        Some(title) => format!("{}\n{}", title, p.url),
+                        None => p.url.clone(),
+                    };
+                    sent.push(send_txt(self, to, txt, &p).reply_markup(nav_markup).await?);
+                    return Ok(sent);
+                };
 
                 for img_file in first {
-                    self.send_document(to, InputFile::file(img_file)).await?;
+                    sent.push(send_img(self, to, img_file, &p, settings, None, None).await?);
                 }
-
-                let _ = self.send_document(to, InputFile::file(last))
-                    .reply_markup(InlineKeyboardMarkup::new(vec![nav_btns(&p)]))
-                    .await?;
+                sent.push(send_img(self, to, last, &p, settings, Some(nav_markup), None).await?);
             }
-            false => {
-                for img_file in imgs {
-                    self.send_document(to, InputFile::file(img_file)).await?;
+            Some((last_txt, first_txts)) => {
+                for img_file in imgs.iter() {
+                    sent.push(send_img(self, to, img_file, &p, settings, None, None).await?);
                 }
 
-                let re_arranged_txts = resize_text(&p.text, 2000);
-                let (last_txt, first_txts) = re_arranged_txts.split_last().unwrap();
-
                 for txt in first_txts {
-                    self.send_message(to, txt)
-                        .reply_markup(InlineKeyboardMarkup::new(vec![translate_btn(txt)]))
-                        .await?;
+                    let markup =
+                        InlineKeyboardMarkup::new(vec![translate_btn(txt, p.idx, settings)]);
+                    sent.push(send_html(self, to, txt, &p, markup).await?);
                 }
 
-                self.send_message(to, last_txt)
-                    .reply_markup(InlineKeyboardMarkup::new(vec![
-                        translate_btn(last_txt),
-                        nav_btns(&p),
-                    ]))
-                    .await?;
+                let markup = InlineKeyboardMarkup::new(vec![
+                    translate_btn(last_txt, p.idx, settings),
+                    nav_btns(&p, settings.lang),
+                    site_btns(&p, settings.lang),
+                ]);
+                sent.push(send_html(self, to, last_txt, &p, markup).await?);
             }
         }
 
-        Ok(())
+        Ok(sent)
     }
-}
 
-fn resize_text(txt: &[String], max_len: usize) -> Vec<String> {
-    txt.iter()
-        // could fail if txt element itself is bigger than max_len
-        // will fix later. or won't.
-        .fold(vec![], |mut acc, t| match (acc.pop(), t.len()) {
-            (None, _) => {
-                acc.push(t.to_string());
-                acc
+    // a single message: the first page's image, titles of all the pages and a button to start reading.
+    // just the titles if the first page has no image
+    async fn send_digest(
+        &self,
+        pages: Vec<PageToSend>,
+        to: ChatId,
+        settings: &ChatSettings,
+    ) -> SentResult {
+        log::info!("digest of {} pages for {}", pages.len(), to);
+
+        // it has to have at least one page, hence unwrap
+        let first = pages.first().unwrap();
+
+        let caption = pages
+            .iter()
+            .fold(Msg::Digest(pages.len()).text(settings.lang), |acc, p| {
+                let line = format!("\n#{} {}", p.idx, p.title.clone().unwrap_or_default());
+                match utf16_len(&acc) + utf16_len(&line) <= MAX_CAPTION_LEN {
+                    true => acc + &line,
+                    false => acc,
+                }
+            });
+
+        let read_btn = InlineKeyboardButton::callback(
+            Msg::ReadFromHere.text(settings.lang),
+            format!("n-{}", first.idx),
+        );
+
+        let markup = InlineKeyboardMarkup::new(vec![vec![read_btn]]);
+
+        let sent = match first.img_files().first() {
+            Some(img_file) => {
+                let mut req = self
+                    .send_photo(to, InputFile::file(img_file))
+                    .caption(caption)
+                    .reply_markup(markup);
+                req.message_thread_id = first.thread_id;
+                req.await?
             }
-            (Some(last), l) if (l + last.len()) < max_len => {
-                acc.push(format!("{}\n\n{}", last, t));
-                acc
+            None => send_html(self, to, &html_text::escape(&caption), first, markup).await?,
+        };
+
+        Ok(vec![sent])
+    }
+
+    // one announcement instead of a banner and a whole page for each of the new pages
+    async fn send_burst(
+        &self,
+        first: &PageToSend,
+        count: usize,
+        to: ChatId,
+        settings: &ChatSettings,
+    ) -> SentResult {
+        log::info!("burst of {} pages from {} for {}", count, first.idx, to);
+
+        let read_btn = InlineKeyboardButton::callback(
+            Msg::StartReading.text(settings.lang),
+            format!("n-{}", first.idx),
+        );
+
+        let sent = send_txt(
+            self,
+            to,
+            Msg::NewPages(count, first.idx).text(settings.lang),
+            first,
+        )
+        .reply_markup(InlineKeyboardMarkup::new(vec![vec![read_btn]]))
+        .await?;
+
+        Ok(vec![sent])
+    }
+
+    // goes after every round, members vote on it to move on
+    async fn send_club_progress(
+        &self,
+        club: &Club,
+        last_idx: usize,
+        to: ChatId,
+        thread_id: Option<i32>,
+        settings: &ChatSettings,
+    ) -> SentResult {
+        let read = club.next.saturating_sub(1).min(last_idx);
+        let percent = (read + 1) * 100 / (last_idx + 1);
+
+        let mut req = self
+            .send_message(
+                to,
+                Msg::ClubProgress(read, last_idx, percent).text(settings.lang),
+            )
+            .reply_markup(club_kb(club, settings.lang));
+        req.message_thread_id = thread_id;
+        let sent = req.await?;
+
+        Ok(vec![sent])
+    }
+
+    // images with a caption and no buttons, channels can't press them anyway.
+    // the caption goes on the very first album
+    async fn publish_album(&self, p: &PageToSend, n: usize, to: Recipient) -> SentResult {
+        log::info!("publishing album {} of page {} to {}", n, p.idx, to);
+
+        let tags = vec![Some(format!("#{}", p.idx)), p.chapter_tag()]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(" ");
+        let footer = format!("{}\n{}", tags, p.url);
+        // the title gets whatever room is left by the tags and the link
+        let caption = match &p.title {
+            Some(title) => format!(
+                "{}\n{}",
+                html_text::truncate(title, MAX_CAPTION_LEN - utf16_len(&footer) - 1),
+                footer
+            ),
+            None => footer,
+        };
+
+        let caption = Some(caption).filter(|_| n == 0);
+        let albums = p.albums();
+        let sent = match (albums.get(n).map(Vec::as_slice), caption) {
+            (None, Some(caption)) => vec![self.send_message(to, caption).await?],
+            (None, None) => vec![],
+            (Some([img_file]), caption) => {
+                let mut req = self.send_photo(to, InputFile::file(img_file));
+                req.caption = caption;
+                vec![req.await?]
+            }
+            (Some(album), caption) => {
+                let media = album.iter().enumerate().map(|(i, img_file)| {
+                    let photo = InputMediaPhoto::new(InputFile::file(img_file));
+                    InputMedia::Photo(match (i, &caption) {
+                        (0, Some(caption)) => photo.caption(caption.clone()),
+                        _ => photo,
+                    })
+                });
+                self.send_media_group(to, media).await?
             }
-            (Some(last), _) => {
-                acc.push(last);
-                acc.push(t.to_string());
-                acc
+        };
+        Ok(sent)
+    }
+
+    // the previous pin may be gone already, it's no reason not to pin the new one
+    async fn pin(
+        &self,
+        to: ChatId,
+        message_id: MessageId,
+        replaced: Option<MessageId>,
+    ) -> Result<(), RequestError> {
+        if let Some(replaced) = replaced {
+            let _ = self.unpin_chat_message(to).message_id(replaced).await;
+        }
+        self.pin_chat_message(to, message_id)
+            .disable_notification(true)
+            .await?;
+        Ok(())
+    }
+
+    // one by one, some of them may be deleted by users already. returns how many were deleted
+    async fn delete_messages(&self, to: ChatId, ids: Vec<MessageId>) -> usize {
+        let mut deleted = 0;
+        for id in ids {
+            match self.delete_message(to, id).await {
+                Ok(_) => deleted += 1,
+                Err(e) => log::warn!("error deleting {} in {}: {}", id, to, e),
             }
-        })
+        }
+        deleted
+    }
 }
 
-fn translate_btn(txt: &str) -> Vec<InlineKeyboardButton> {
-    let txt_url_encoded = urlencoding::encode(txt);
+// every message of a page goes to the same topic and with the same sound
+fn send_txt(
+    bot: &Bot,
+    to: ChatId,
+    txt: impl Into<String>,
+    p: &PageToSend,
+) -> <Bot as Requester>::SendMessage {
+    let mut req = bot.send_message(to, txt).disable_notification(p.is_silent);
+    req.message_thread_id = p.thread_id;
+    req
+}
 
-    vec![InlineKeyboardButton::url(
-        "TRANSLATE",
-        Url::parse(
-            format!(
-                "https://translate.google.com/?sl=en&tl=ru&text={}&op=translate",
-                txt_url_encoded
-            )
-            .as_str(),
-        )
-        .unwrap(),
-    )]
+// pages scraped before formatting was kept are plain text, which isn't always valid html.
+// those go as text only, the way telegram would show them
+async fn send_html(
+    bot: &Bot,
+    to: ChatId,
+    txt: &str,
+    p: &PageToSend,
+    markup: InlineKeyboardMarkup,
+) -> Result<Message, RequestError> {
+    let sent = send_txt(bot, to, txt, p)
+        .parse_mode(ParseMode::Html)
+        .reply_markup(markup.clone())
+        .await;
+    match sent {
+        Err(e) if is_parse_error(&e) => {
+            send_txt(bot, to, html_text::strip_tags(txt), p)
+                .reply_markup(markup)
+                .await
+        }
+        sent => sent,
+    }
+}
+
+// telegram adds details to the description, e.g. `can't parse entities: Unsupported start tag`,
+// those don't match teloxide's variant
+pub fn is_parse_error(e: &RequestError) -> bool {
+    match e {
+        RequestError::Api(ApiError::CantParseEntities) => true,
+        RequestError::Api(ApiError::Unknown(s)) => s.contains("can't parse entities"),
+        _ => false,
+    }
+}
+
+// banner, title and text of a page in one caption, unless they are too long for it
+// or there's no image to caption
+fn compact_caption(p: &PageToSend, lang: Lang) -> Option<String> {
+    if p.img_files().is_empty() {
+        return None;
+    }
+    let caption = [
+        Some(Msg::GreatNews.text(lang)).filter(|_| p.is_new),
+        p.header(),
+    ]
+    .into_iter()
+    .flatten()
+    .map(|t| html_text::escape(&t))
+    .chain(p.text.iter().cloned())
+    .collect::<Vec<_>>()
+    .join("\n\n");
+    Some(caption).filter(|c| html_text::text_len(c) <= MAX_CAPTION_LEN)
+}
+
+// as a file or as a photo, depending on chat's settings. captions are html
+async fn send_img(
+    bot: &Bot,
+    to: ChatId,
+    img_file: &str,
+    p: &PageToSend,
+    settings: &ChatSettings,
+    markup: Option<InlineKeyboardMarkup>,
+    caption: Option<String>,
+) -> Result<Message, RequestError> {
+    let parse_mode = caption.as_ref().map(|_| ParseMode::Html);
+    let file = InputFile::file(img_file);
+    let reply_markup = markup.map(ReplyMarkup::InlineKeyboard);
+    match settings.format {
+        DeliveryFormat::Document => {
+            let mut req = bot
+                .send_document(to, file)
+                .disable_notification(p.is_silent);
+            req.reply_markup = reply_markup;
+            req.message_thread_id = p.thread_id;
+            req.caption = caption;
+            req.parse_mode = parse_mode;
+            req.await
+        }
+        DeliveryFormat::Photo => {
+            let mut req = bot.send_photo(to, file).disable_notification(p.is_silent);
+            req.reply_markup = reply_markup;
+            req.message_thread_id = p.thread_id;
+            req.caption = caption;
+            req.parse_mode = parse_mode;
+            req.await
+        }
+    }
+}
+
+// translated in the chat by `x-{idx}` callback if there's a translation server,
+// otherwise it's just a link to google translate
+fn translate_btn(txt: &str, idx: usize, settings: &ChatSettings) -> Vec<InlineKeyboardButton> {
+    let label = Msg::Translate.text(settings.lang);
+    match LIBRETRANSLATE_URL.as_ref() {
+        Some(_) => vec![InlineKeyboardButton::callback(label, format!("x-{}", idx))],
+        None => vec![InlineKeyboardButton::url(
+            label,
+            GoogleLinkTranslator::link(&html_text::strip_tags(txt), &settings.translate_to),
+        )],
+    }
 }
 
-fn nav_btns(p: &PageToSend) -> Vec<InlineKeyboardButton> {
+fn nav_btns(p: &PageToSend, lang: Lang) -> Vec<InlineKeyboardButton> {
     let mut nav_but_row = Vec::<InlineKeyboardButton>::new();
     if p.idx > 0 {
         nav_but_row.push(InlineKeyboardButton::callback(
-            "PREV",
+            Msg::Prev.text(lang),
             format!("n-{}", p.idx - 1),
         ));
     }
+    nav_but_row.push(InlineKeyboardButton::callback(
+        Msg::Bookmark.text(lang),
+        format!("b-{}", p.idx),
+    ));
+    if p.has_notes {
+        nav_but_row.push(InlineKeyboardButton::callback(
+            Msg::Notes.text(lang),
+            format!("t-{}", p.idx),
+        ));
+    }
     if p.has_next {
         nav_but_row.push(InlineKeyboardButton::callback(
-            "NEXT",
+            Msg::Next.text(lang),
             format!("n-{}", p.idx + 1),
         ));
     }
     nav_but_row
 }
+
+pub fn club_kb(club: &Club, lang: Lang) -> InlineKeyboardMarkup {
+    InlineKeyboardMarkup::new(vec![vec![InlineKeyboardButton::callback(
+        Msg::ReadyForNext(club.votes.len(), club.quorum).text(lang),
+        format!("v-{}", club.next),
+    )]])
+}
+
+// the original page on the site, and a page to forward to friends through the inline mode.
+// neither is a callback, so there's no 64 bytes limit to mind
+fn site_btns(p: &PageToSend, lang: Lang) -> Vec<InlineKeyboardButton> {
+    let open_btn = Url::parse(&p.url)
+        .ok()
+        .map(|url| InlineKeyboardButton::url(Msg::OnSite.text(lang), url));
+    // inline search takes a number for a page idx
+    let share_btn =
+        InlineKeyboardButton::switch_inline_query(Msg::Share.text(lang), p.idx.to_string());
+
+    open_btn.into_iter().chain([share_btn]).collect()
+}
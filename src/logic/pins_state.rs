@@ -0,0 +1,43 @@
+use std::path;
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use lazy_static::lazy_static;
+use tokio::fs;
+
+use crate::cfg::DATA_PATH;
+use crate::domain::pins_state::PinsState;
+
+lazy_static! {
+    static ref STATE_PATH: String = format!("{}/pins_state.txt", DATA_PATH.as_str());
+}
+
+#[async_trait]
+pub trait PinsStateManager {
+    async fn load_pins_state(&self) -> PinsState;
+    async fn save_pins_state(&self, state: &PinsState);
+}
+
+// prod implementation
+#[derive(Clone, Copy)]
+pub struct PinsStateManagerImpl {}
+
+#[async_trait]
+impl PinsStateManager for PinsStateManagerImpl {
+    async fn load_pins_state(&self) -> PinsState {
+        match path::Path::new(STATE_PATH.as_str()).exists() {
+            false => PinsState::default(),
+            true => fs::read_to_string(STATE_PATH.as_str())
+                .await
+                .map_err(|e| e.to_string())
+                .and_then(|s| PinsState::from_str(s.as_str()))
+                .unwrap(),
+        }
+    }
+
+    async fn save_pins_state(&self, state: &PinsState) {
+        fs::write(STATE_PATH.as_str(), state.to_string())
+            .await
+            .unwrap()
+    }
+}
@@ -0,0 +1,43 @@
+use std::path;
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use lazy_static::lazy_static;
+use tokio::fs;
+
+use crate::cfg::DATA_PATH;
+use crate::domain::queue_state::QueueState;
+
+lazy_static! {
+    static ref STATE_PATH: String = format!("{}/queue_state.txt", DATA_PATH.as_str());
+}
+
+#[async_trait]
+pub trait QueueStateManager {
+    async fn load_queue_state(&self) -> QueueState;
+    async fn save_queue_state(&self, state: &QueueState);
+}
+
+// prod implementation
+#[derive(Clone, Copy)]
+pub struct QueueStateManagerImpl {}
+
+#[async_trait]
+impl QueueStateManager for QueueStateManagerImpl {
+    async fn load_queue_state(&self) -> QueueState {
+        match path::Path::new(STATE_PATH.as_str()).exists() {
+            false => QueueState::default(),
+            true => fs::read_to_string(STATE_PATH.as_str())
+                .await
+                .map_err(|e| e.to_string())
+                .and_then(|s| QueueState::from_str(s.as_str()))
+                .unwrap(),
+        }
+    }
+
+    async fn save_queue_state(&self, state: &QueueState) {
+        fs::write(STATE_PATH.as_str(), state.to_string())
+            .await
+            .unwrap()
+    }
+}
@@ -0,0 +1,43 @@
+use std::path;
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use lazy_static::lazy_static;
+use tokio::fs;
+
+use crate::cfg::DATA_PATH;
+use crate::domain::reread_state::RereadState;
+
+lazy_static! {
+    static ref STATE_PATH: String = format!("{}/reread_state.txt", DATA_PATH.as_str());
+}
+
+#[async_trait]
+pub trait RereadStateManager {
+    async fn load_reread_state(&self) -> RereadState;
+    async fn save_reread_state(&self, state: &RereadState);
+}
+
+// prod implementation
+#[derive(Clone, Copy)]
+pub struct RereadStateManagerImpl {}
+
+#[async_trait]
+impl RereadStateManager for RereadStateManagerImpl {
+    async fn load_reread_state(&self) -> RereadState {
+        match path::Path::new(STATE_PATH.as_str()).exists() {
+            false => RereadState::default(),
+            true => fs::read_to_string(STATE_PATH.as_str())
+                .await
+                .map_err(|e| e.to_string())
+                .and_then(|s| RereadState::from_str(s.as_str()))
+                .unwrap(),
+        }
+    }
+
+    async fn save_reread_state(&self, state: &RereadState) {
+        fs::write(STATE_PATH.as_str(), state.to_string())
+            .await
+            .unwrap()
+    }
+}
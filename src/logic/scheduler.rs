@@ -0,0 +1,231 @@
+use std::error::Error;
+
+use chrono::Utc;
+use teloxide::types::{ChatId, Recipient};
+use teloxide::{ApiError, RequestError};
+
+use crate::cfg::CHANNELS;
+
+use crate::domain::page_to_send::PageToSend;
+use crate::domain::settings_state::{Cleanup, Digest};
+use crate::logic::bot_flow::{send_new_pages, send_page};
+use crate::logic::bot_state::BotStateManager;
+use crate::logic::page_sender::{is_parse_error, PageSender};
+
+// pages queued during chats' quiet hours are delivered once those are over
+pub async fn deliver_queued(state: &impl BotStateManager, sender: &impl PageSender) {
+    let now = Utc::now();
+    for chat_id in state.queued_chat_ids().await {
+        if state.settings(chat_id).await.is_quiet_at(now) {
+            continue;
+        }
+
+        let idxs = state.take_queued(chat_id).await;
+        log::info!("delivering {} queued pages to {}", idxs.len(), chat_id);
+        let mut pages = vec![];
+        for idx in idxs {
+            if let Some(p) = state.by_idx(idx).await {
+                pages.push(PageToSend::fresh_page(p));
+            }
+        }
+        send_new_pages(state, sender, pages, chat_id).await;
+    }
+}
+
+// digests go out at chat's chosen hour. if digests got switched off meanwhile, what's left goes right away
+pub async fn deliver_digests(state: &impl BotStateManager, sender: &impl PageSender) {
+    let now = Utc::now();
+    for chat_id in state.digest_chat_ids().await {
+        let settings = state.settings(chat_id).await;
+        if settings.digest != Digest::Off && !settings.is_digest_time(now) {
+            continue;
+        }
+
+        let thread_id = state.thread_of(chat_id).await;
+        let mut pages = vec![];
+        for idx in state.take_digest(chat_id).await {
+            match state.by_idx(idx).await {
+                Some(p) => pages.push(PageToSend {
+                    thread_id,
+                    ..PageToSend::fresh_page(p)
+                }),
+                None => log::warn!("no page {} for {}'s digest", idx, chat_id),
+            }
+        }
+        if pages.is_empty() {
+            continue;
+        }
+        let count = pages.len();
+        if let Err(e) = sender.send_digest(pages, chat_id, &settings).await {
+            log::error!(
+                "error sending digest of {} pages to {}: {}",
+                count,
+                chat_id,
+                e
+            );
+        }
+    }
+}
+
+// rereading chats get their next pages once a day
+pub async fn deliver_rereads(state: &impl BotStateManager, sender: &impl PageSender) {
+    let now = Utc::now();
+    for chat_id in state.reread_chat_ids().await {
+        let settings = state.settings(chat_id).await;
+        let today = settings.local_date(now);
+        let mut reread = match state.reread(chat_id).await {
+            Some(r) if r.is_due(settings.local_hour(now), today) => r,
+            _ => continue,
+        };
+
+        log::info!(
+            "rereading {} pages from {} for {}",
+            reread.per_day,
+            reread.next,
+            chat_id
+        );
+        let thread_id = state.thread_of(chat_id).await;
+        for _ in 0..reread.per_day {
+            // caught up with the last page, new ones come the usual way
+            let Some(p) = state.by_idx(reread.next).await else {
+                break;
+            };
+            let p = PageToSend {
+                thread_id,
+                ..PageToSend::old_page(p)
+            };
+            if send_page(state, sender, p, chat_id).await.is_err() {
+                break;
+            }
+            reread.next += 1;
+        }
+        reread.last_sent = Some(today);
+        state.set_reread(chat_id, reread).await;
+    }
+}
+
+// book clubs get their next round once enough members are ready for it
+pub async fn deliver_club_rounds(state: &impl BotStateManager, sender: &impl PageSender) {
+    let now = Utc::now();
+    for chat_id in state.club_chat_ids().await {
+        let settings = state.settings(chat_id).await;
+        let today = settings.local_date(now);
+        let mut club = match state.club(chat_id).await {
+            Some(c) if c.is_due(settings.local_hour(now), today) => c,
+            _ => continue,
+        };
+
+        let thread_id = state.thread_of(chat_id).await;
+        let mut sent_any = false;
+        for _ in 0..club.per_round {
+            let Some(p) = state.by_idx(club.next).await else {
+                break;
+            };
+            let p = PageToSend {
+                thread_id,
+                ..PageToSend::old_page(p)
+            };
+            if send_page(state, sender, p, chat_id).await.is_err() {
+                break;
+            }
+            club.next += 1;
+            sent_any = true;
+        }
+        // caught up with the last page, waiting for new ones
+        if !sent_any {
+            continue;
+        }
+
+        log::info!("book club {} is at {} now", chat_id, club.next);
+        club.votes.clear();
+        club.last_sent = Some(today);
+        state.set_club(chat_id, club.clone()).await;
+
+        let last_idx = state.last_idx().await.unwrap_or(club.next);
+        let _ = sender
+            .send_club_progress(&club, last_idx, chat_id, thread_id, &settings)
+            .await;
+    }
+}
+
+// channels get every page after the last one posted there. a channel new to the bot starts with the next new page
+pub async fn publish_to_channels(state: &impl BotStateManager, sender: &impl PageSender) {
+    let last_idx = match state.last_idx().await {
+        Some(idx) => idx,
+        None => return,
+    };
+
+    for channel in CHANNELS.iter() {
+        let from = match state.channel_posted(channel).await {
+            Some(posted) => posted + 1,
+            None => {
+                state.set_channel_posted(channel, last_idx).await;
+                continue;
+            }
+        };
+
+        // a page goes album by album, a retry starts with the one that failed
+        'pages: for idx in from..=last_idx {
+            let Some(p) = state.by_idx(idx).await else {
+                continue;
+            };
+            let p = PageToSend::fresh_page(p);
+            let from_album = state.channel_albums_posted(channel).await;
+            for n in from_album..p.album_count() {
+                match sender
+                    .publish_album(&p, n, channel_recipient(channel))
+                    .await
+                {
+                    Ok(_) => state.set_channel_albums_posted(channel, n + 1).await,
+                    Err(e) if is_page_error(&*e) => {
+                        log::error!("skipping page {} for {}: {}", idx, channel, e);
+                        break;
+                    }
+                    Err(e) => {
+                        log::error!("error publishing page {} to {}: {}", idx, channel, e);
+                        break 'pages;
+                    }
+                }
+            }
+            state.set_channel_posted(channel, idx).await;
+        }
+    }
+}
+
+// errors about the page itself, it would fail just the same on the next poll.
+// anything else, outages and a channel out of reach included, is worth another try
+fn is_page_error(e: &(dyn Error + Send + Sync + 'static)) -> bool {
+    match e.downcast_ref::<RequestError>() {
+        Some(RequestError::Api(ApiError::PhotoAsInputFileRequired)) => true,
+        // an image file gone missing or unreadable
+        Some(RequestError::Io(_)) => true,
+        Some(e) => is_parse_error(e),
+        None => false,
+    }
+}
+
+// pages read long enough ago go away, according to chats' cleanup policy. the pinned one stays
+pub async fn clean_up_sent(state: &impl BotStateManager, sender: &impl PageSender) {
+    let now = Utc::now();
+    for chat_id in state.sent_chat_ids().await {
+        let cleanup = state.settings(chat_id).await.cleanup;
+        if cleanup == Cleanup::Off {
+            continue;
+        }
+
+        let pinned = state.pinned(chat_id).await;
+        let expired = state.take_expired_sent(chat_id, cleanup, pinned, now).await;
+        if !expired.is_empty() {
+            log::info!("cleaning up {} messages in {}", expired.len(), chat_id);
+            sender.delete_messages(chat_id, expired).await;
+        }
+    }
+}
+
+// channels are configured either by id or by @username
+fn channel_recipient(channel: &str) -> Recipient {
+    match channel.parse::<i64>() {
+        Ok(id) => Recipient::Id(ChatId(id)),
+        Err(_) => Recipient::ChannelUsername(channel.to_string()),
+    }
+}
@@ -2,12 +2,15 @@ use std::pin::Pin;
 use std::time::Instant;
 
 use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
 use futures::future::{join_all, TryFutureExt};
 use futures::{stream, Stream};
 use lazy_static::lazy_static;
-use scraper::{Html, Selector};
+use reqwest::Url;
+use scraper::{ElementRef, Html, Node, Selector};
 
 use crate::cfg::DATA_PATH;
+use crate::domain::html_text::{escape, text_len};
 use crate::domain::ksbd_page::KsbdPage;
 use crate::domain::ksbd_page_error::GetPageError;
 
@@ -15,6 +18,9 @@ lazy_static! {
     static ref SELECTOR_IMG: Selector = Selector::parse("#comic img").unwrap();
     static ref SELECTOR_NEXT: Selector = Selector::parse("#sidebar-over-comic > div > table > tbody > tr > td.comic_navi_right > a.navi.comic-nav-next.navi-next").unwrap();
     static ref SELECTOR_ENTRY: Selector = Selector::parse(".entry p").unwrap();
+    static ref SELECTOR_PUBLISHED: Selector =
+        Selector::parse("meta[property='article:published_time']").unwrap();
+    static ref SELECTOR_POST_DATE: Selector = Selector::parse(".post-date").unwrap();
 }
 
 #[async_trait]
@@ -63,12 +69,22 @@ impl KsbdScraper for KsbdScraperImpl {
             .map(|u| u.to_string())
             .collect::<Vec<_>>();
 
+        // it's been requested just fine, hence unwrap
+        let page_url = Url::parse(url).unwrap();
+
+        // a paragraph can't have an empty line inside, those separate paragraphs.
+        // paragraphs of images only, e.g. `<p><a><img></a></p>`, have nothing to show
         let text = document
             .select(&SELECTOR_ENTRY)
-            .flat_map(|e| {
-                e.text()
-                    .map(|t| t.replace('\t', "%09").replace('\n', "%0D%0A"))
+            .map(|e| {
+                let mut html = telegram_html(e, &page_url).trim().to_string();
+                while html.contains("\n\n") {
+                    html = html.replace("\n\n", "\n");
+                }
+                html
             })
+            .filter(|p| text_len(p) > 0)
+            .map(|p| p.replace('\t', "%09").replace('\n', "%0D%0A"))
             .collect::<Vec<_>>()
             .join("%0D%0A%0D%0A");
 
@@ -77,6 +93,25 @@ impl KsbdScraper for KsbdScraperImpl {
             .next()
             .and_then(|e| e.value().attr("href"));
 
+        // precise timestamp from the meta tag, or at least a day from the post info
+        let published = document
+            .select(&SELECTOR_PUBLISHED)
+            .next()
+            .and_then(|e| e.value().attr("content"))
+            .and_then(|d| DateTime::parse_from_rfc3339(d).ok())
+            .map(|d| d.with_timezone(&Utc))
+            .or_else(|| {
+                document
+                    .select(&SELECTOR_POST_DATE)
+                    .next()
+                    .and_then(|e| {
+                        NaiveDate::parse_from_str(e.text().collect::<String>().trim(), "%B %d, %Y")
+                            .ok()
+                    })
+                    .and_then(|d| d.and_hms_opt(0, 0, 0))
+                    .map(|d| DateTime::<Utc>::from_utc(d, Utc))
+            });
+
         Ok(KsbdPage {
             idx,
             title,
@@ -84,6 +119,7 @@ impl KsbdScraper for KsbdScraperImpl {
             imgs: img_urls,
             next: next_url.map(|u| u.to_string()),
             text,
+            published,
         })
     }
 
@@ -170,3 +206,41 @@ impl KsbdScraper for KsbdScraperImpl {
         Box::pin(res_stream)
     }
 }
+
+// telegram knows a few formatting tags only, anything else is reduced to its text
+fn telegram_html(el: ElementRef, page_url: &Url) -> String {
+    el.children()
+        .map(|node| match node.value() {
+            Node::Text(t) => escape(&t.replace('\n', " ")),
+            Node::Element(e) => {
+                let inner = ElementRef::wrap(node)
+                    .map(|el| telegram_html(el, page_url))
+                    .unwrap_or_default();
+                let tag = match e.name() {
+                    "b" | "strong" => "b",
+                    "i" | "em" => "i",
+                    "u" => "u",
+                    "s" | "strike" | "del" => "s",
+                    "code" => "code",
+                    "a" => {
+                        // relative links are resolved, anything but web links is left as text
+                        return match e
+                            .attr("href")
+                            .and_then(|href| page_url.join(href).ok())
+                            .filter(|href| matches!(href.scheme(), "http" | "https"))
+                        {
+                            Some(href) => {
+                                format!("<a href=\"{}\">{}</a>", escape(href.as_str()), inner)
+                            }
+                            None => inner,
+                        };
+                    }
+                    "br" => return "\n".to_string(),
+                    _ => return inner,
+                };
+                format!("<{tag}>{}</{tag}>", inner, tag = tag)
+            }
+            _ => String::new(),
+        })
+        .collect()
+}
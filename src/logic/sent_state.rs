@@ -0,0 +1,43 @@
+use std::path;
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use lazy_static::lazy_static;
+use tokio::fs;
+
+use crate::cfg::DATA_PATH;
+use crate::domain::sent_state::SentState;
+
+lazy_static! {
+    static ref STATE_PATH: String = format!("{}/sent_state.txt", DATA_PATH.as_str());
+}
+
+#[async_trait]
+pub trait SentStateManager {
+    async fn load_sent_state(&self) -> SentState;
+    async fn save_sent_state(&self, state: &SentState);
+}
+
+// prod implementation
+#[derive(Clone, Copy)]
+pub struct SentStateManagerImpl {}
+
+#[async_trait]
+impl SentStateManager for SentStateManagerImpl {
+    async fn load_sent_state(&self) -> SentState {
+        match path::Path::new(STATE_PATH.as_str()).exists() {
+            false => SentState::default(),
+            true => fs::read_to_string(STATE_PATH.as_str())
+                .await
+                .map_err(|e| e.to_string())
+                .and_then(|s| SentState::from_str(s.as_str()))
+                .unwrap(),
+        }
+    }
+
+    async fn save_sent_state(&self, state: &SentState) {
+        fs::write(STATE_PATH.as_str(), state.to_string())
+            .await
+            .unwrap()
+    }
+}
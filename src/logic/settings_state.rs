@@ -0,0 +1,43 @@
+use std::path;
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use lazy_static::lazy_static;
+use tokio::fs;
+
+use crate::cfg::DATA_PATH;
+use crate::domain::settings_state::SettingsState;
+
+lazy_static! {
+    static ref STATE_PATH: String = format!("{}/settings_state.txt", DATA_PATH.as_str());
+}
+
+#[async_trait]
+pub trait SettingsStateManager {
+    async fn load_settings_state(&self) -> SettingsState;
+    async fn save_settings_state(&self, state: &SettingsState);
+}
+
+// prod implementation
+#[derive(Clone, Copy)]
+pub struct SettingsStateManagerImpl {}
+
+#[async_trait]
+impl SettingsStateManager for SettingsStateManagerImpl {
+    async fn load_settings_state(&self) -> SettingsState {
+        match path::Path::new(STATE_PATH.as_str()).exists() {
+            false => SettingsState::default(),
+            true => fs::read_to_string(STATE_PATH.as_str())
+                .await
+                .map_err(|e| e.to_string())
+                .and_then(|s| SettingsState::from_str(s.as_str()))
+                .unwrap(),
+        }
+    }
+
+    async fn save_settings_state(&self, state: &SettingsState) {
+        fs::write(STATE_PATH.as_str(), state.to_string())
+            .await
+            .unwrap()
+    }
+}
@@ -0,0 +1,85 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures::TryFutureExt;
+use reqwest::Url;
+
+use crate::domain::translate_error::TranslateError;
+
+pub enum Translation {
+    // translated right away, could be sent to the chat as is
+    Text(String),
+    // translation is somewhere else, e.g. in a browser
+    Link(Url),
+}
+
+#[async_trait]
+pub trait Translator {
+    async fn translate(&self, txt: &str, to: &str) -> Result<Translation, TranslateError>;
+}
+
+// fallback implementation, just a link to google translate
+#[derive(Clone)]
+pub struct GoogleLinkTranslator {}
+
+impl GoogleLinkTranslator {
+    pub fn link(txt: &str, to: &str) -> Url {
+        Url::parse(
+            format!(
+                "https://translate.google.com/?sl=en&tl={}&text={}&op=translate",
+                to,
+                urlencoding::encode(txt)
+            )
+            .as_str(),
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait]
+impl Translator for GoogleLinkTranslator {
+    async fn translate(&self, txt: &str, to: &str) -> Result<Translation, TranslateError> {
+        Ok(Translation::Link(GoogleLinkTranslator::link(txt, to)))
+    }
+}
+
+// a stalled translation shouldn't keep the chat waiting for good
+static TIMEOUT_SECS: u64 = 30;
+
+// self-hosted LibreTranslate or anything speaking its api
+#[derive(Clone)]
+pub struct LibreTranslator {
+    pub url: String,
+    pub api_key: Option<String>,
+}
+
+#[async_trait]
+impl Translator for LibreTranslator {
+    async fn translate(&self, txt: &str, to: &str) -> Result<Translation, TranslateError> {
+        let url = format!("{}/translate", self.url.trim_end_matches('/'));
+        let mut form = vec![
+            ("q", txt),
+            ("source", "en"),
+            ("target", to),
+            ("format", "text"),
+        ];
+        if let Some(api_key) = &self.api_key {
+            form.push(("api_key", api_key.as_str()));
+        }
+
+        let body = reqwest::Client::new()
+            .post(url.as_str())
+            .timeout(Duration::from_secs(TIMEOUT_SECS))
+            .form(&form)
+            .send()
+            .and_then(|r| r.text())
+            .map_err(|e| TranslateError::req_err(&url, e))
+            .await?;
+
+        serde_json::from_str::<serde_json::Value>(&body)
+            .ok()
+            .and_then(|v| v["translatedText"].as_str().map(|t| t.to_string()))
+            .map(Translation::Text)
+            .ok_or(TranslateError::bad_response(&url, &body))
+    }
+}
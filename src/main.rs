@@ -1,6 +1,7 @@
 use std::sync::Arc;
 use std::time;
 
+use chrono::Utc;
 use dotenv::dotenv;
 use futures::StreamExt;
 use teloxide::dispatching::dialogue::InMemStorage;
@@ -8,17 +9,35 @@ use teloxide::dispatching::{dialogue, UpdateHandler};
 use teloxide::prelude::*;
 use tokio::fs;
 
-use crate::cfg::DATA_PATH;
+use crate::cfg::{DATA_PATH, LIBRETRANSLATE_API_KEY, LIBRETRANSLATE_URL};
 use crate::domain::bot_cmd::Command;
 use crate::domain::page_to_send::PageToSend;
+use crate::domain::settings_state::{Digest, QuietMode};
+use crate::logic::bookmarks_state::BookmarksStateManagerImpl;
 use crate::logic::bot_flow::*;
 use crate::logic::bot_state::BotStateManager;
 use crate::logic::bot_state::{BotStateManagerImpl, BotStateManagerInit};
+use crate::logic::channels_state::ChannelsStateManagerImpl;
+use crate::logic::club_state::ClubStateManagerImpl;
+use crate::logic::digest_state::DigestStateManagerImpl;
+use crate::logic::file_ids_state::FileIdsStateManagerImpl;
+use crate::logic::history_state::HistoryStateManagerImpl;
+use crate::logic::notes_state::NotesStateManagerImpl;
 use crate::logic::page_sender::PageSender;
 use crate::logic::pages_state::PagesStateManagerImpl;
+use crate::logic::pins_state::PinsStateManagerImpl;
+use crate::logic::queue_state::QueueStateManagerImpl;
+use crate::logic::reread_state::RereadStateManagerImpl;
+use crate::logic::scheduler::{
+    clean_up_sent, deliver_club_rounds, deliver_digests, deliver_queued, deliver_rereads,
+    publish_to_channels,
+};
 use crate::logic::scraper::KsbdScraper;
 use crate::logic::scraper::KsbdScraperImpl;
+use crate::logic::sent_state::SentStateManagerImpl;
+use crate::logic::settings_state::SettingsStateManagerImpl;
 use crate::logic::subs_state::SubsStateManagerImpl;
+use crate::logic::translator::{GoogleLinkTranslator, LibreTranslator, Translator};
 
 mod cfg;
 mod domain;
@@ -36,9 +55,27 @@ async fn main() {
         KsbdScraperImpl {},
         PagesStateManagerImpl {},
         SubsStateManagerImpl {},
+        FileIdsStateManagerImpl {},
+        BookmarksStateManagerImpl {},
+        NotesStateManagerImpl {},
+        HistoryStateManagerImpl {},
+        SettingsStateManagerImpl {},
+        QueueStateManagerImpl {},
+        DigestStateManagerImpl {},
+        RereadStateManagerImpl {},
+        ClubStateManagerImpl {},
+        ChannelsStateManagerImpl {},
+        PinsStateManagerImpl {},
+        SentStateManagerImpl {},
     )
     .await;
 
+    log::info!("starting publication dates back-fill...");
+    let bot_state_manager_for_backfill = bot_state_manager.clone();
+    tokio::spawn(async move {
+        backfill_published(&bot_state_manager_for_backfill, &KsbdScraperImpl {}).await
+    });
+
     log::info!("starting new pages watcher...");
     let bot = Bot::from_env();
     let bot_for_updater = bot.clone();
@@ -53,15 +90,40 @@ async fn main() {
                 &KsbdScraperImpl {},
             )
             .await;
+            publish_to_channels(&bot_state_manager_for_updater, &bot_for_updater).await;
+            tokio::time::sleep(delay).await
+        }
+    });
+    let translator: Arc<dyn Translator + Send + Sync> = match LIBRETRANSLATE_URL.as_ref() {
+        Some(url) => Arc::new(LibreTranslator {
+            url: url.to_string(),
+            api_key: LIBRETRANSLATE_API_KEY.clone(),
+        }),
+        None => Arc::new(GoogleLinkTranslator {}),
+    };
+
+    log::info!("starting scheduler...");
+    let bot_for_scheduler = bot.clone();
+    let bot_state_manager_for_scheduler = bot_state_manager.clone();
+    tokio::spawn(async move {
+        let delay = time::Duration::from_secs(60);
+        loop {
+            deliver_queued(&bot_state_manager_for_scheduler, &bot_for_scheduler).await;
+            deliver_digests(&bot_state_manager_for_scheduler, &bot_for_scheduler).await;
+            deliver_rereads(&bot_state_manager_for_scheduler, &bot_for_scheduler).await;
+            deliver_club_rounds(&bot_state_manager_for_scheduler, &bot_for_scheduler).await;
+            clean_up_sent(&bot_state_manager_for_scheduler, &bot_for_scheduler).await;
             tokio::time::sleep(delay).await
         }
     });
+
     log::info!("ksbd bot started...");
 
     Dispatcher::builder(bot, schema())
         .dependencies(dptree::deps![
             // need to cast, otherwise dptree is unable to find manager dependency
             Arc::new(bot_state_manager) as Arc<dyn BotStateManager + Send + Sync>,
+            translator,
             InMemStorage::<()>::new()
         ])
         .enable_ctrlc_handler()
@@ -76,19 +138,53 @@ fn schema() -> UpdateHandler<Box<dyn std::error::Error + Send + Sync + 'static>>
     use dptree::case;
 
     let command_handler = teloxide::filter_command::<Command, _>()
-        .branch(case![Command::Start].endpoint(start))
+        .branch(case![Command::Start(payload)].endpoint(start))
         .branch(case![Command::Help].endpoint(help))
         .branch(case![Command::First].endpoint(first))
         .branch(case![Command::Last].endpoint(last))
-        .branch(case![Command::Jump].endpoint(jump_menu));
+        .branch(case![Command::Jump].endpoint(jump_menu))
+        .branch(case![Command::Random].endpoint(random))
+        .branch(case![Command::OnThisDay].endpoint(on_this_day))
+        .branch(case![Command::Bookmarks].endpoint(bookmarks))
+        .branch(case![Command::Note(text)].endpoint(note))
+        .branch(case![Command::Notes].endpoint(notes))
+        .branch(case![Command::History].endpoint(history))
+        .branch(case![Command::Settings].endpoint(settings))
+        .branch(case![Command::Timezone(offset)].endpoint(timezone))
+        .branch(case![Command::Reread(args)].endpoint(reread))
+        .branch(case![Command::Pause].endpoint(pause))
+        .branch(case![Command::Club(args)].endpoint(club))
+        .branch(case![Command::Clean].endpoint(clean));
 
-    let message_handler = Update::filter_message().branch(command_handler);
+    // plain text replies to page messages become notes
+    let reply_handler = dptree::filter(|msg: Message| {
+        msg.text().is_some() && msg.reply_to_message().and_then(page_idx_of).is_some()
+    })
+    .endpoint(note_reply);
+
+    let migration_handler =
+        dptree::filter(|msg: Message| msg.migrate_to_chat_id().is_some()).endpoint(chat_migration);
+
+    let message_handler = Update::filter_message()
+        .branch(migration_handler)
+        .branch(command_handler)
+        .branch(reply_handler);
 
     let callback_query_handler = Update::filter_callback_query().endpoint(nav_callback);
 
-    dialogue::enter::<Update, InMemStorage<()>, (), _>()
-        .branch(message_handler)
-        .branch(callback_query_handler)
+    // inline queries have no chat, so they can't enter the dialogue
+    let inline_query_handler = Update::filter_inline_query().endpoint(inline_pages);
+
+    let my_chat_member_handler = Update::filter_my_chat_member().endpoint(chat_member_update);
+
+    dptree::entry()
+        .branch(inline_query_handler)
+        .branch(my_chat_member_handler)
+        .branch(
+            dialogue::enter::<Update, InMemStorage<()>, (), _>()
+                .branch(message_handler)
+                .branch(callback_query_handler),
+        )
 }
 
 // screw it. I'm done. gonna leave it like this. just a function in a main. hardcore to the mega.
@@ -114,15 +210,39 @@ async fn check_new_page_and_send(
                             log::info!("new page {}", p);
                         });
 
+                        // queued and digest pages are looked up by idx once quiet hours are over
+                        // or the digest is due, which can happen before every subscriber is done with
+                        state.add_pages(new_pages.clone()).await;
+
+                        let now = Utc::now();
                         for chat_id in state.subs_chat_ids().await {
-                            for p in new_pages.clone() {
-                                let _ = sender
-                                    .send_full_page(PageToSend::fresh_page(p), chat_id)
-                                    .await;
+                            let settings = state.settings(chat_id).await;
+                            let idxs = new_pages.iter().map(|p| p.idx).collect::<Vec<_>>();
+                            match (
+                                settings.digest,
+                                settings.is_quiet_at(now),
+                                settings.quiet_mode,
+                            ) {
+                                _ if !settings.notify => {}
+                                (Digest::Daily | Digest::Weekly, _, _) => {
+                                    state.add_to_digest(chat_id, idxs).await
+                                }
+                                (_, true, QuietMode::Queue) => {
+                                    state.queue_pages(chat_id, idxs).await
+                                }
+                                (_, is_quiet, _) => {
+                                    let to_send = new_pages
+                                        .iter()
+                                        .cloned()
+                                        .map(|p| PageToSend {
+                                            is_silent: is_quiet,
+                                            ..PageToSend::fresh_page(p)
+                                        })
+                                        .collect();
+                                    send_new_pages(state, sender, to_send, chat_id).await;
+                                }
                             }
                         }
-
-                        state.add_pages(new_pages).await;
                     }
                 }
             }
@@ -131,3 +251,35 @@ async fn check_new_page_and_send(
         None => log::warn!("no last page to watch from"),
     };
 }
+
+// pages scraped before publication dates were stored are re-requested once to get their dates
+async fn backfill_published(state: &impl BotStateManager, scraper: &impl KsbdScraper) {
+    static BATCH_SIZE: usize = 20;
+
+    let to_backfill = state.without_published().await;
+    if to_backfill.is_empty() {
+        return;
+    }
+    log::info!(
+        "back-filling publication dates of {} pages",
+        to_backfill.len()
+    );
+
+    for batch in to_backfill.chunks(BATCH_SIZE) {
+        let mut dates = vec![];
+        for (idx, url) in batch {
+            match scraper.request_page(*idx, url).await {
+                Ok(p) => {
+                    if p.published.is_none() {
+                        log::warn!("no publication date at {}", url);
+                    }
+                    dates.push((*idx, p.published))
+                }
+                Err(e) => log::error!("error requesting page: {}", e),
+            }
+        }
+        state.set_published(dates).await;
+    }
+
+    log::info!("publication dates have been back-filled");
+}